@@ -18,7 +18,7 @@ async fn main() {
         );
         headers.insert(
             "apiKey",
-            HeaderValue::from_str(&format!("{}", api_key)).expect("header value is invalid"),
+            HeaderValue::from_str(&api_key).expect("header value is invalid"),
         );
     }
 
@@ -0,0 +1,113 @@
+use std::io;
+
+use crate::{build::executor::Executor, model::options::ListObjectsOptions, Storage};
+
+/// A `Storage` scoped to a single bucket, so the bucket id isn't passed to every call.
+///
+/// Each method creates a new `Builder` from the `Storage`, see `Storage::from`.
+pub struct BucketHandle<'a> {
+    storage: &'a Storage,
+    bucket_id: String,
+}
+
+impl<'a> BucketHandle<'a> {
+    pub(crate) fn new(storage: &'a Storage, bucket_id: &str) -> Self {
+        Self {
+            storage,
+            bucket_id: bucket_id.to_string(),
+        }
+    }
+
+    /// The id of the bucket.
+    pub fn id(&self) -> &str {
+        &self.bucket_id
+    }
+
+    /// Uploads the file at `file_path` as `object`, see `Builder::upload_object`.
+    pub async fn upload(&self, object: &str, file_path: &str) -> Result<Executor, io::Error> {
+        self.storage
+            .from()
+            .upload_object(&self.bucket_id, object, file_path)
+            .await
+    }
+
+    /// Retrieves the content of `object`, see `Builder::get_object`.
+    pub fn download(&self, object: &str) -> Executor {
+        self.storage.from().get_object(&self.bucket_id, object)
+    }
+
+    /// Lists the objects of the bucket, see `Builder::list_objects_from`.
+    pub fn list(&self, options: ListObjectsOptions) -> Executor {
+        self.storage
+            .from()
+            .list_objects_from(&self.bucket_id, options)
+    }
+
+    /// Deletes `object`, see `Builder::delete_object`.
+    pub fn delete(&self, object: &str) -> Executor {
+        self.storage.from().delete_object(&self.bucket_id, object)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use reqwest::Method;
+
+    use crate::{
+        build::builder::BodyType, model::options::ListObjectsOptions, testing::MockBackend, Storage,
+    };
+
+    #[test]
+    fn test_bucket_handle_paths() {
+        let storage = Storage::new("http://localhost/storage/v1");
+        let bucket = storage.bucket("thefux");
+        assert_eq!(bucket.id(), "thefux");
+
+        let executor = bucket.download("docs/btc.pdf");
+        assert_eq!(executor.builder.method, Method::GET);
+        assert_eq!(
+            executor.builder.url.path(),
+            "/storage/v1/object/thefux/docs/btc.pdf"
+        );
+
+        let executor = bucket.delete("btc.pdf");
+        assert_eq!(executor.builder.method, Method::DELETE);
+        assert_eq!(
+            executor.builder.url.path(),
+            "/storage/v1/object/thefux/btc.pdf"
+        );
+
+        let executor = bucket.list(ListObjectsOptions::new("docs"));
+        assert_eq!(executor.builder.method, Method::POST);
+        assert_eq!(
+            executor.builder.url.path(),
+            "/storage/v1/object/list/thefux"
+        );
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"prefix":"docs"}"#),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bucket_handle_upload() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/btc.pdf"}"#);
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        storage
+            .bucket("thefux")
+            .upload("btc.pdf", "out/test.pdf")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let request = &backend.requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url.path(), "/object/thefux/btc.pdf");
+    }
+}
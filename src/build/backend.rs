@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use reqwest::{Client, Error, Request, Response};
+
+/// The transport used to send the requests built by a `Builder`.
+///
+/// By default requests are sent with the `reqwest::Client` of the `Storage`,
+/// implement this trait to plug in a different transport, e.g. a mock in tests.
+#[async_trait]
+pub trait HttpBackend: Send + Sync {
+    /// Sends the request and returns the response.
+    async fn send(&self, request: Request) -> Result<Response, Error>;
+}
+
+#[async_trait]
+impl HttpBackend for Client {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        self.execute(request).await
+    }
+}
@@ -1,8 +1,12 @@
 use reqwest::{header::HeaderValue, Method};
+use serde::de::IgnoredAny;
 
 use crate::{
     build::{builder::Builder, executor::Executor},
-    model::bucket::{BucketUpdate, NewBucket},
+    model::{
+        bucket::{BucketDetails, BucketUpdate, NewBucket},
+        errors::{self, ErrorKind},
+    },
 };
 
 use super::builder::BodyType;
@@ -34,6 +38,81 @@ impl Builder {
         self.create_executor()
     }
 
+    /// find the first bucket matching the predicate, all buckets are fetched and filtered
+    /// on the client
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Returns `true` for the bucket to find.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<BucketDetails>, errors::Error>` - The first matching bucket, if any.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let bucket = Storage::new_with_config(config)
+    ///         .from()
+    ///         .find_bucket(|bucket| bucket.name.starts_with("invoices-"))
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn find_bucket<P>(self, predicate: P) -> Result<Option<BucketDetails>, errors::Error>
+    where
+        P: Fn(&BucketDetails) -> bool,
+    {
+        let buckets = self
+            .get_buckets()
+            .execute_from::<Vec<BucketDetails>>()
+            .await?;
+        Ok(buckets.into_iter().find(|bucket| predicate(bucket)))
+    }
+
+    /// find a bucket by its name, see `find_bucket`
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the bucket.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<BucketDetails>, errors::Error>` - The bucket, if it exists.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let bucket = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_bucket_by_name("thefux")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn get_bucket_by_name(
+        self,
+        name: &str,
+    ) -> Result<Option<BucketDetails>, errors::Error> {
+        self.find_bucket(|bucket| bucket.name == name).await
+    }
+
     /// create a new bucket
     ///
     /// # Arguments
@@ -61,6 +140,10 @@ impl Builder {
     ///     .execute();
     /// ```
     pub fn create_bucket(mut self, body: &str) -> Executor {
+        self.headers
+            .lock()
+            .unwrap()
+            .insert("Content-Type", HeaderValue::from_static("application/json"));
         self.method = Method::POST;
         self.url.path_segments_mut().unwrap().push("bucket");
         self.body = Some(BodyType::StringBody(body.into()));
@@ -69,13 +152,15 @@ impl Builder {
 
     /// create a new bucket using a struct
     ///
+    /// The bucket name is validated with `NewBucket::validate` before building the request.
+    ///
     /// # Arguments
     ///
     /// * `body` - The `NewBucket` struct containing the request body.
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or an `InvalidBucketName` error.
     ///
     /// # Example
     /// ```
@@ -91,19 +176,66 @@ impl Builder {
     /// let storage = Storage::new_with_config(config)
     ///     .from()
     ///     .create_bucket_from(NewBucket::new("thefux".to_string()))
+    ///     .unwrap()
     ///     .execute();
     /// ```
-    pub fn create_bucket_from(mut self, body: NewBucket) -> Executor {
-        self.method = Method::POST;
-        self.url.path_segments_mut().unwrap().push("bucket");
-        self.body = Some(BodyType::StringBody(
-            serde_json::to_string(&body).unwrap_or_default(),
-        ));
-        self.create_executor()
+    pub fn create_bucket_from(self, body: NewBucket) -> Result<Executor, errors::Error> {
+        body.validate()?;
+        let mut builder = self.model_body(&body);
+        builder.method = Method::POST;
+        builder.url.path_segments_mut().unwrap().push("bucket");
+        Ok(builder.create_executor())
+    }
+
+    /// create a new bucket, ignoring the error if the bucket already exists
+    ///
+    /// The storage api has no idempotent bucket creation, a bucket that already exists is
+    /// rejected with a `Duplicate` error (`ErrorKind::Duplicate`) which is ignored here.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The `NewBucket` struct containing the request body.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, errors::Error>` - `true` if the bucket was created, `false` if it already existed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::bucket::NewBucket,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let created = Storage::new_with_config(config)
+    ///         .from()
+    ///         .create_bucket_if_not_exists(NewBucket::new("thefux".to_string()))
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn create_bucket_if_not_exists(self, body: NewBucket) -> Result<bool, errors::Error> {
+        match self
+            .create_bucket_from(body)?
+            .execute_from::<IgnoredAny>()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::Duplicate => Ok(false),
+            Err(error) => Err(error),
+        }
     }
 
     /// empty the bucket
     ///
+    /// The storage answers with `{ "message": "Successfully emptied" }`,
+    /// use `execute_from::<Response>()` to check for success.
+    ///
     /// # Arguments
     ///
     /// * `bucket_id` - The identifier of the bucket to empty.
@@ -117,23 +249,30 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
+    ///     model::object::Response,
     /// };
     /// use dotenv::dotenv;
     ///
-    /// dotenv().ok();
-    /// let config = SupabaseConfig::default();
-    /// let storage = Storage::new_with_config(config)
-    ///     .from()
-    ///     .empty_bucket("thefux")
-    ///     .execute();
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .empty_bucket("thefux")
+    ///         .execute_from::<Response>()
+    ///         .await;
+    /// }
     /// ```
     pub fn empty_bucket(mut self, bucket_id: &str) -> Executor {
         self.method = Method::POST;
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("bucket")
-            .push(bucket_id);
+            .push(bucket_id)
+            .push("empty");
 
         self.create_executor()
     }
@@ -164,6 +303,7 @@ impl Builder {
     ///     .execute();
     /// ```
     pub fn get_bucket_details(mut self, bucket_id: &str) -> Executor {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -203,6 +343,7 @@ impl Builder {
             .unwrap()
             .insert("Content-Type", HeaderValue::from_static("application/json"));
         self.method = Method::PUT;
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -212,15 +353,30 @@ impl Builder {
         self.create_executor()
     }
 
+    fn update_bucket_intern(self, bucket_id: &str, body: BucketUpdate) -> Executor {
+        let mut builder = self.model_body(&body);
+        builder.method = Method::PUT;
+        let bucket_id = builder.bucket_id(bucket_id);
+        builder
+            .url
+            .path_segments_mut()
+            .unwrap()
+            .push("bucket")
+            .push(bucket_id);
+        builder.create_executor()
+    }
+
     /// update bucket using a struct
     ///
+    /// The update is validated with `BucketUpdate::validate` before building the request.
+    ///
     /// # Arguments
     ///
     /// * `bucket_id` - The identifier of the bucket to empty.
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or the validation error.
     ///
     /// # Example
     /// ```
@@ -236,30 +392,79 @@ impl Builder {
     /// let storage = Storage::new_with_config(config)
     ///     .from()
     ///     .update_bucket_from("thefux", BucketUpdate {
-    ///         public: false,
+    ///         public: Some(false),
     ///         file_size_limit: Some(0),
     ///         allowed_mime_types: Some(vec!["application/pdf".to_string()]),
     ///     })
+    ///     .unwrap()
     ///     .execute();
     /// ```
-    pub fn update_bucket_from(mut self, bucket_id: &str, body: BucketUpdate) -> Executor {
-        self.method = Method::PUT;
-        self.url
-            .path_segments_mut()
-            .unwrap()
-            .push("bucket")
-            .push(bucket_id);
-        self.body = Some(BodyType::StringBody(
-            serde_json::to_string(&body).unwrap_or_default(),
-        ));
-        self.create_executor()
+    pub fn update_bucket_from(
+        self,
+        bucket_id: &str,
+        body: BucketUpdate,
+    ) -> Result<Executor, errors::Error> {
+        body.validate()?;
+        Ok(self.update_bucket_intern(bucket_id, body))
     }
 
-    /// delete a bucket
+    /// update a bucket and return its details
+    ///
+    /// The storage api answers updates with a message and ignores `Prefer: return=representation`,
+    /// so the bucket is read back with a follow-up `get_bucket_details` request.
     ///
     /// # Arguments
     ///
-    /// * `bucket_id` - The identifier of the bucket to empty.
+    /// * `bucket_id` - The identifier of the bucket to update.
+    /// * `body` - The `BucketUpdate` struct containing the fields to update.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<BucketDetails, errors::Error>` - The updated bucket, or the error of either request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::bucket::BucketUpdate,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let bucket = Storage::new_with_config(config)
+    ///         .from()
+    ///         .update_bucket_returning("thefux", BucketUpdate {
+    ///             public: Some(true),
+    ///             ..Default::default()
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn update_bucket_returning(
+        self,
+        bucket_id: &str,
+        body: BucketUpdate,
+    ) -> Result<BucketDetails, errors::Error> {
+        let details = self.fork();
+        self.update_bucket_from(bucket_id, body)?
+            .execute_from::<IgnoredAny>()
+            .await?;
+        details
+            .get_bucket_details(bucket_id)
+            .execute_from::<BucketDetails>()
+            .await
+    }
+
+    /// make a bucket public, only the `public` field is updated
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - The identifier of the bucket to update.
     ///
     /// # Returns
     ///
@@ -277,11 +482,90 @@ impl Builder {
     /// let config = SupabaseConfig::default();
     /// let storage = Storage::new_with_config(config)
     ///     .from()
-    ///     .delete_bucket("thefux")
+    ///     .make_bucket_public("thefux")
     ///     .execute();
     /// ```
+    pub fn make_bucket_public(self, bucket_id: &str) -> Executor {
+        self.update_bucket_intern(
+            bucket_id,
+            BucketUpdate {
+                public: Some(true),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// make a bucket private, only the `public` field is updated
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - The identifier of the bucket to update.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// dotenv().ok();
+    /// let config = SupabaseConfig::default();
+    /// let storage = Storage::new_with_config(config)
+    ///     .from()
+    ///     .make_bucket_private("thefux")
+    ///     .execute();
+    /// ```
+    pub fn make_bucket_private(self, bucket_id: &str) -> Executor {
+        self.update_bucket_intern(
+            bucket_id,
+            BucketUpdate {
+                public: Some(false),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// delete a bucket, the bucket has to be empty
+    ///
+    /// The storage answers with `{ "message": "Successfully deleted" }`,
+    /// use `execute_from::<Response>()` to check for success.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - The identifier of the bucket to delete.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::object::Response,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .delete_bucket("thefux")
+    ///         .execute_from::<Response>()
+    ///         .await;
+    /// }
+    /// ```
     pub fn delete_bucket(mut self, bucket_id: &str) -> Executor {
         self.method = Method::DELETE;
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -290,3 +574,343 @@ impl Builder {
         self.create_executor()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use reqwest::{header::HeaderMap, Client, Method};
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        build::builder::{BodyType, Builder},
+        model::{
+            bucket::{BucketDetails, BucketUpdate, NewBucket},
+            errors::{BucketNameError, ErrorKind},
+            object::Response,
+        },
+        testing::MockBackend,
+        Storage,
+    };
+
+    const DUPLICATE: &str =
+        r#"{"statusCode":"409","error":"Duplicate","message":"The resource already exists"}"#;
+
+    fn builder() -> Builder {
+        Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+    }
+
+    #[test]
+    fn test_create_bucket_content_type() {
+        let executor = builder().create_bucket(r#"{"name":"thefux"}"#);
+
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("Content-Type")
+                .unwrap(),
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn test_create_bucket_from_content_type() {
+        let executor = builder()
+            .create_bucket_from(NewBucket::new("thefux".to_string()))
+            .unwrap();
+
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("Content-Type")
+                .unwrap(),
+            "application/json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_bucket_returning() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully updated"}"#);
+        backend.push_response(
+            200,
+            &[],
+            r#"{"id":"thefux","name":"thefux","public":true,"file_size_limit":null,"allowed_mime_types":null}"#,
+        );
+
+        let bucket = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .update_bucket_returning(
+                "thefux",
+                BucketUpdate {
+                    public: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(bucket.public);
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(requests[1].method, "GET");
+        assert_eq!(requests[1].url.path(), "/bucket/thefux");
+        assert!(requests[0].headers.get("Prefer").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_bucket_returning_error() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            404,
+            &[],
+            r#"{"statusCode":"404","error":"Bucket not found","message":"Bucket not found"}"#,
+        );
+
+        let error = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .update_bucket_returning("thefux", BucketUpdate::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_update_bucket_from_invalid_mime_type() {
+        let result = builder().update_bucket_from(
+            "thefux",
+            BucketUpdate {
+                allowed_mime_types: Some(vec![
+                    "image/*".to_string(),
+                    "application pdf".to_string(),
+                ]),
+                ..Default::default()
+            },
+        );
+
+        let error = result.err().unwrap();
+        assert_eq!(error.error, "InvalidMimeType");
+        assert_eq!(error.kind(), ErrorKind::InvalidRequest);
+        assert!(error.message.contains("application pdf"));
+    }
+
+    #[test]
+    fn test_make_bucket_public() {
+        let executor = builder().make_bucket_public("thefux");
+
+        assert_eq!(executor.builder.method, Method::PUT);
+        assert_eq!(executor.builder.url.path(), "/bucket/thefux");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"public":true}"#),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_make_bucket_private() {
+        let executor = builder().make_bucket_private("thefux");
+
+        assert_eq!(executor.builder.method, Method::PUT);
+        assert_eq!(executor.builder.url.path(), "/bucket/thefux");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"public":false}"#),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_bucket_from_duplicate() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(400, &[], DUPLICATE);
+
+        let error = Storage::new("http://localhost")
+            .with_backend(backend)
+            .from()
+            .create_bucket_from(NewBucket::new("thefux".to_string()))
+            .unwrap()
+            .execute_from::<serde_json::Value>()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Duplicate);
+    }
+
+    #[test]
+    fn test_create_bucket_from_invalid_name() {
+        for name in ["TheFux", "the fux"] {
+            let result = builder().create_bucket_from(NewBucket::new(name.to_string()));
+            assert!(matches!(result, Err(error) if error.error == "InvalidBucketName"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_bucket_if_not_exists() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"name":"thefux"}"#);
+        backend.push_response(409, &[], DUPLICATE);
+        let storage = Storage::new("http://localhost").with_backend(backend);
+
+        for expected in [true, false] {
+            let created = storage
+                .from()
+                .create_bucket_if_not_exists(NewBucket::new("thefux".to_string()))
+                .await
+                .unwrap();
+            assert_eq!(created, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_empty_bucket_response() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully emptied"}"#);
+
+        let response = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .empty_bucket("thefux")
+            .execute_from::<Response>()
+            .await
+            .unwrap();
+
+        assert_eq!(response.message, "Successfully emptied");
+        let requests = backend.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].url.path(), "/bucket/thefux/empty");
+    }
+
+    #[tokio::test]
+    async fn test_delete_bucket_response() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully deleted"}"#);
+
+        let response = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .delete_bucket("thefux")
+            .execute_from::<Response>()
+            .await
+            .unwrap();
+
+        assert_eq!(response.message, "Successfully deleted");
+        let requests = backend.requests();
+        assert_eq!(requests[0].method, Method::DELETE);
+        assert_eq!(requests[0].url.path(), "/bucket/thefux");
+    }
+
+    #[tokio::test]
+    async fn test_find_bucket() {
+        let buckets = r#"[
+            {"id":"thefux","name":"thefux","public":false},
+            {"id":"invoices-2023","name":"invoices-2023","public":false},
+            {"id":"invoices-2024","name":"invoices-2024","public":true}
+        ]"#;
+        let backend = Arc::new(MockBackend::new());
+        for _ in 0..3 {
+            backend.push_response(200, &[], buckets);
+        }
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let bucket = storage
+            .from()
+            .find_bucket(|bucket| bucket.name.starts_with("invoices-") && bucket.public)
+            .await
+            .unwrap();
+        assert_eq!(bucket.unwrap().id, "invoices-2024");
+
+        let bucket = storage.from().get_bucket_by_name("thefux").await.unwrap();
+        assert_eq!(bucket.unwrap().name, "thefux");
+
+        let bucket = storage.from().get_bucket_by_name("missing").await.unwrap();
+        assert!(bucket.is_none());
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|request| request.method == Method::GET && request.url.path() == "/bucket"));
+    }
+
+    #[test]
+    fn test_bucket_id_normalized_across_methods() {
+        let storage = Storage::new("http://localhost");
+
+        let executor = storage.from().get_bucket_details(" thefux ");
+        assert_eq!(executor.builder.url.path(), "/bucket/thefux");
+
+        let executor = storage.from().delete_object(" thefux ", "btc.pdf");
+        assert_eq!(executor.builder.url.path(), "/object/thefux/btc.pdf");
+
+        let parts = storage
+            .from()
+            .move_object(" thefux ", "a.pdf", "b.pdf")
+            .dry_run()
+            .unwrap();
+        assert!(parts
+            .body_summary
+            .unwrap()
+            .contains(r#""bucketId":"thefux""#));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_bucket_id_not_sent() {
+        let backend = Arc::new(MockBackend::new());
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let error = storage
+            .from()
+            .get_bucket_details("my/bucket")
+            .execute_from::<BucketDetails>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.error, "InvalidBucketName");
+        assert_eq!(
+            error.message,
+            BucketNameError::InvalidCharacter('/').to_string()
+        );
+
+        let error = storage
+            .from()
+            .delete_object("my bucket", "btc.pdf")
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.message,
+            BucketNameError::InvalidCharacter(' ').to_string()
+        );
+
+        let error = storage
+            .from()
+            .get_object("  ", "btc.pdf")
+            .execute_with_meta()
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, BucketNameError::Empty.to_string());
+
+        let error = storage
+            .from()
+            .move_object("TheFux", "a.pdf", "b.pdf")
+            .dry_run()
+            .unwrap_err();
+        assert_eq!(
+            error.message,
+            BucketNameError::InvalidCharacter('T').to_string()
+        );
+
+        assert!(backend.requests().is_empty());
+    }
+}
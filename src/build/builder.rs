@@ -1,13 +1,40 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+};
 
-use super::executor::Executor;
+use super::{backend::HttpBackend, executor::Executor};
+use crate::model::{
+    bucket::normalize_bucket_id,
+    errors,
+    options::{content_disposition_value, FileOptions},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::{write::GzEncoder, Compression};
 use reqwest::{
-    header::{HeaderMap, HeaderValue, IntoHeaderName},
+    header::{
+        HeaderMap, HeaderName, HeaderValue, IntoHeaderName, AUTHORIZATION, CONTENT_DISPOSITION,
+        CONTENT_ENCODING, CONTENT_TYPE, IF_MATCH,
+    },
     Body, Client, Error, Method, RequestBuilder, Response,
 };
 
+use serde::Serialize;
 use url::Url;
 
+/// Default maximum number of bytes read from the body of a failed response, 1 MiB.
+pub const DEFAULT_MAX_ERROR_BODY_SIZE: usize = 1024 * 1024;
+
+/// Returns the bearer token to authorize a request with, invoked for every request.
+pub type TokenProvider = Arc<dyn Fn() -> String + Send + Sync>;
+
+/// Splits an object key into path segments, leading, trailing and repeated slashes are dropped
+/// so `/folder/file.pdf` and `folder/file.pdf` address the same object.
+pub(crate) fn object_segments(object: &str) -> impl Iterator<Item = &str> {
+    object.split('/').filter(|segment| !segment.is_empty())
+}
+
 #[derive(Debug)]
 pub enum BodyType {
     StringBody(String),
@@ -17,9 +44,27 @@ pub enum BodyType {
 pub struct Builder {
     pub url: Url,
     pub headers: Arc<Mutex<HeaderMap>>,
-    pub client: Arc<Mutex<Client>>,
+    pub client: Client,
     pub method: Method,
     pub body: Option<BodyType>,
+    /// transport used to send the request, the client is used if not set
+    pub backend: Option<Arc<dyn HttpBackend>>,
+    /// gzip string bodies before sending them, streamed bodies are sent as they are
+    pub compress_body: bool,
+    /// file options used by uploads when none are passed
+    pub default_file_options: Option<FileOptions>,
+    /// overrides the `Authorization` header with a fresh bearer token
+    pub token_provider: Option<TokenProvider>,
+    /// name of the query parameter carrying the token of signed urls
+    pub token_param: String,
+    /// path of the storage url, `path` replaces the segments below it
+    pub base_path: String,
+    /// indent json bodies built by `body_json` instead of sending them compact
+    pub pretty_json: bool,
+    /// error found while building the request, returned by the executor instead of sending it
+    invalid_request: Option<errors::Error>,
+    /// maximum number of bytes read from the body of a failed response
+    pub max_error_body_size: usize,
 }
 
 impl Builder {
@@ -40,20 +85,32 @@ impl Builder {
     /// use std::sync::{Arc, Mutex};
     ///
     /// let url = Url::parse("http://localhost").unwrap();
-    /// let builder = Builder::new(url, Arc::new(Mutex::new(HeaderMap::new())), Arc::new(Mutex::new(Client::new())));
+    /// let builder = Builder::new(url, Arc::new(Mutex::new(HeaderMap::new())), Client::new());
     /// ```
-    pub fn new(url: Url, headers: Arc<Mutex<HeaderMap>>, client: Arc<Mutex<Client>>) -> Self {
+    pub fn new(url: Url, headers: Arc<Mutex<HeaderMap>>, client: Client) -> Self {
         Self {
+            base_path: url.path().to_string(),
             url,
             headers,
             client,
             method: Method::GET,
             body: None,
+            backend: None,
+            compress_body: false,
+            default_file_options: None,
+            token_provider: None,
+            token_param: "token".to_string(),
+            pretty_json: false,
+            invalid_request: None,
+            max_error_body_size: DEFAULT_MAX_ERROR_BODY_SIZE,
         }
     }
 
     /// Constructs and returns a `RequestBuilder` instance based on the current `Builder` configuration.
     ///
+    /// A token of the token provider which is no valid header value leaves out the
+    /// `Authorization` header, the `Executor` methods return it as an error instead.
+    ///
     /// # Returns
     ///
     /// * `RequestBuilder` - The constructed `RequestBuilder` instance.
@@ -63,18 +120,25 @@ impl Builder {
     //         .headers(self.headers)
     //         .body(self.body.unwrap_or_default())
     // }
-    pub fn build(self) -> RequestBuilder {
+    pub fn build(mut self) -> RequestBuilder {
+        self.apply_token_provider();
         // let headers = self.headers.lock().unwrap();
-        let headers = Arc::try_unwrap(self.headers).unwrap();
+        let headers = Arc::try_unwrap(self.headers).unwrap().into_inner().unwrap();
+
         let mut request = self
             .client
-            .lock()
-            .unwrap()
             .request(self.method, self.url.to_string())
-            .headers(headers.into_inner().unwrap());
+            .headers(headers);
 
         if let Some(body) = self.body {
             match body {
+                BodyType::StringBody(body_string) if self.compress_body => {
+                    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                    encoder.write_all(body_string.as_bytes()).unwrap();
+                    request = request
+                        .header(CONTENT_ENCODING, HeaderValue::from_static("gzip"))
+                        .body(encoder.finish().unwrap());
+                }
                 BodyType::StringBody(body_string) => request = request.body(body_string),
                 BodyType::ReqwestBody(reqwest_body) => request = request.body(reqwest_body),
             }
@@ -105,7 +169,7 @@ impl Builder {
     ///
     /// let url = Url::parse("http://localhost").unwrap();
     ///
-    /// let _ = Builder::new(url, Arc::new(Mutex::new(HeaderMap::new())), Arc::new(Mutex::new(Client::new())))
+    /// let _ = Builder::new(url, Arc::new(Mutex::new(HeaderMap::new())), Client::new())
     ///     .header("Authorization", HeaderValue::from_static("Bearer <token>"));
     /// ```
     pub fn header(self, key: impl IntoHeaderName, value: HeaderValue) -> Self {
@@ -113,6 +177,402 @@ impl Builder {
         self
     }
 
+    /// Adds a header from a caller supplied string.
+    ///
+    /// A value which is no valid header value is returned by every `Executor` method,
+    /// like an invalid bucket id.
+    pub(crate) fn checked_header(mut self, key: HeaderName, value: &str) -> Self {
+        match HeaderValue::from_str(value) {
+            Ok(value) => self.header(key, value),
+            Err(_) => {
+                self.invalid_request
+                    .get_or_insert(invalid_header(key.as_str()));
+                self
+            }
+        }
+    }
+
+    /// Serializes the value as json and sets it as the request body,
+    /// along with the `Content-Type: application/json` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to serialize.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, serde_json::Error>` - The updated `Builder` instance, or the serialization error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{Storage, model::bucket::NewBucket};
+    ///
+    /// let builder = Storage::new("http://localhost")
+    ///     .from()
+    ///     .body_json(&NewBucket::new("thefux".to_string()))
+    ///     .unwrap();
+    /// ```
+    pub fn body_json<T: Serialize>(mut self, value: &T) -> Result<Self, serde_json::Error> {
+        let body = if self.pretty_json {
+            serde_json::to_string_pretty(value)?
+        } else {
+            serde_json::to_string(value)?
+        };
+        self.headers
+            .lock()
+            .unwrap()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.body = Some(BodyType::StringBody(body));
+        Ok(self)
+    }
+
+    /// Sets the json body of one of the request models of this crate, see `body_json`.
+    ///
+    /// The models only hold strings, numbers, booleans and lists of those, which always serialize.
+    pub(crate) fn model_body<T: Serialize>(self, model: &T) -> Self {
+        self.body_json(model)
+            .expect("the request models always serialize")
+    }
+
+    /// Sets a string body along with its `Content-Type` header, for endpoints or formats
+    /// which have no dedicated method yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The body of the request.
+    /// * `content_type` - The value of the `Content-Type` header.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, errors::Error>` - The updated `Builder` instance, or an `InvalidContentType`
+    ///   error if `content_type` is not a valid header value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reqwest::Method;
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .text_body("<Delete></Delete>".to_string(), "application/xml")
+    ///     .unwrap()
+    ///     .raw_request(Method::POST, &["s3", "thefux"]);
+    /// ```
+    pub fn text_body(mut self, body: String, content_type: &str) -> Result<Self, errors::Error> {
+        let content_type = HeaderValue::from_str(content_type).map_err(|_| errors::Error {
+            status_code: "400".to_string(),
+            error: "InvalidContentType".to_string(),
+            message: format!("{:?} is not a valid content type", content_type),
+            context: None,
+        })?;
+        self.headers
+            .lock()
+            .unwrap()
+            .insert(CONTENT_TYPE, content_type);
+        self.body = Some(BodyType::StringBody(body));
+        Ok(self)
+    }
+
+    /// Indents the json bodies of typed requests, e.g. `create_bucket_from`, for backends which
+    /// expect them formatted. Bodies are compact by default.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{Storage, model::bucket::NewBucket};
+    ///
+    /// let executor = Storage::new("http://localhost")
+    ///     .from()
+    ///     .pretty_json()
+    ///     .create_bucket_from(NewBucket::new("thefux".to_string()))
+    ///     .unwrap();
+    /// ```
+    pub fn pretty_json(mut self) -> Self {
+        self.pretty_json = true;
+        self
+    }
+
+    /// Sets the maximum number of bytes read from the body of a failed response, the rest of
+    /// the body is dropped and the error message notes the truncation.
+    /// Defaults to `DEFAULT_MAX_ERROR_BODY_SIZE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The maximum size of an error body in bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost")
+    ///     .from()
+    ///     .max_error_body_size(64 * 1024)
+    ///     .get_object("thefux", "btc.pdf");
+    /// ```
+    pub fn max_error_body_size(mut self, size: usize) -> Self {
+        self.max_error_body_size = size;
+        self
+    }
+
+    /// Removes the body of the request, e.g. to reuse the builder for another request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let mut builder = Storage::new("http://localhost")
+    ///     .from()
+    ///     .body_json(&"thefux")
+    ///     .unwrap();
+    /// builder.clear_body();
+    /// assert!(builder.body.is_none());
+    /// ```
+    pub fn clear_body(&mut self) {
+        self.body = None;
+    }
+
+    /// Removes all headers except the `Authorization` and `apikey` headers,
+    /// e.g. to reuse the builder for another request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    /// use reqwest::header::HeaderValue;
+    ///
+    /// let mut builder = Storage::new("http://localhost")
+    ///     .from()
+    ///     .header("x-upsert", HeaderValue::from_static("true"));
+    /// builder.clear_headers_extra();
+    /// assert!(builder.headers.lock().unwrap().get("x-upsert").is_none());
+    /// ```
+    pub fn clear_headers_extra(&mut self) {
+        let mut headers = self.headers.lock().unwrap();
+        let retained = [AUTHORIZATION.as_str(), "apikey"]
+            .into_iter()
+            .filter_map(|name| headers.get(name).map(|value| (name, value.clone())))
+            .collect::<Vec<_>>();
+
+        headers.clear();
+        for (name, value) in retained {
+            headers.insert(name, value);
+        }
+    }
+
+    /// Sets the name of the query parameter carrying the token of signed urls, defaults to `token`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the query parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost")
+    ///     .from()
+    ///     .token_param("sig")
+    ///     .get_object_with_pre_assigned_url("thefux", "btc.pdf", "<token>");
+    /// assert_eq!(executor.url().query(), Some("sig=%3Ctoken%3E"));
+    /// ```
+    pub fn token_param(mut self, name: &str) -> Self {
+        self.token_param = name.to_string();
+        self
+    }
+
+    /// Adds an `Idempotency-Key` header to the request.
+    ///
+    /// Useful to safely retry non idempotent requests such as uploads (POST).
+    /// The storage api doesn't deduplicate requests on its own, the key is only honored
+    /// by a gateway or proxy in front of it that supports idempotency keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key, should be unique per logical operation.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance with the idempotency key header added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let _ = Storage::new("http://localhost")
+    ///     .from()
+    ///     .idempotency_key("0d6bb05c-1c2d-4d58-9d8a-5b2b1f0c1c2a")
+    ///     .upload_object("thefux", "btc.pdf", "out/test.pdf");
+    /// ```
+    pub fn idempotency_key(self, key: &str) -> Self {
+        self.checked_header(HeaderName::from_static("idempotency-key"), key)
+    }
+
+    /// Attaches user metadata, e.g. tags or labels, to an uploaded object with the
+    /// `x-metadata` header.
+    ///
+    /// The storage api has no endpoint to update the metadata of an existing object on its own,
+    /// the metadata is replaced when the object is uploaded or updated, e.g. with
+    /// `update_object_async`.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - The metadata, sent as base64 encoded json.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance with the `x-metadata` header added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use supabase_storage::Storage;
+    ///
+    /// let metadata = HashMap::from([("label".to_string(), "invoice".to_string())]);
+    /// let _ = Storage::new("http://localhost")
+    ///     .from()
+    ///     .user_metadata(&metadata)
+    ///     .update_object_async("thefux", "btc.pdf", "out/test.pdf");
+    /// ```
+    pub fn user_metadata(self, metadata: &HashMap<String, String>) -> Self {
+        let json = serde_json::to_string(metadata).expect("a string map is always serializable");
+        self.header(
+            "x-metadata",
+            HeaderValue::from_str(&STANDARD.encode(json)).expect("base64 is a valid header value"),
+        )
+    }
+
+    /// Adds an `If-Match` header to the request.
+    ///
+    /// Used for optimistic concurrency on uploads and updates, the server rejects the request
+    /// with `412 Precondition Failed` (`ErrorKind::PreconditionFailed`) if the object changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `etag` - The `ETag` the object is expected to have.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance with the `If-Match` header added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let _ = Storage::new("http://localhost")
+    ///     .from()
+    ///     .if_match("\"e5d1b1a0c3f8f4d2\"")
+    ///     .update_object_async("thefux", "btc.pdf", "out/test.pdf");
+    /// ```
+    pub fn if_match(self, etag: &str) -> Self {
+        self.checked_header(IF_MATCH, etag)
+    }
+
+    /// Sets the path below the storage url, replacing the segments pushed so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The path segments, each segment is percent-encoded.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let builder = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .path(&["object", "info", "thefux", "btc.pdf"]);
+    /// assert_eq!(builder.url.path(), "/storage/v1/object/info/thefux/btc.pdf");
+    /// ```
+    pub fn path(mut self, segments: &[&str]) -> Self {
+        self.url.set_path(&self.base_path);
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .extend(segments);
+        self
+    }
+
+    /// Sets the HTTP method of the request, e.g. for endpoints built with `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The updated `Builder` instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reqwest::Method;
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .method(Method::PATCH)
+    ///     .path(&["object", "thefux", "btc.pdf"])
+    ///     .create_executor();
+    /// assert_eq!(executor.method(), Method::PATCH);
+    /// ```
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Creates an `Executor` for an arbitrary endpoint of the storage api,
+    /// for endpoints which have no dedicated method yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method.
+    /// * `segments` - The path segments below the storage url, see `path`.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use reqwest::Method;
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .raw_request(Method::GET, &["version"]);
+    /// assert_eq!(executor.url().path(), "/storage/v1/version");
+    /// ```
+    pub fn raw_request(mut self, method: Method, segments: &[&str]) -> Executor {
+        self.method = method;
+        self.path(segments).create_executor()
+    }
+
     /// Executes the constructed HTTP request and returns the response as a `Result`.
     ///
     /// # Returns
@@ -134,7 +594,7 @@ impl Builder {
     ///     let mut headers = HeaderMap::new();
     ///     headers.insert("Authorization", HeaderValue::from_static("Bearer YOUR_ACCESS_TOKEN"));
     ///
-    ///     let builder = Builder::new(url, Arc::new(Mutex::new(headers)), Arc::new(Mutex::new(Client::new())))
+    ///     let builder = Builder::new(url, Arc::new(Mutex::new(headers)), Client::new())
     ///         .header("Authorization", HeaderValue::from_static("Bearer <token>"));
     ///
     ///     // Execute the request and handle the response
@@ -151,7 +611,84 @@ impl Builder {
     /// }
     /// ```
     pub async fn run(self) -> Result<Response, Error> {
-        self.build().send().await
+        match self.backend.clone() {
+            Some(backend) => backend.send(self.build().build()?).await,
+            None => self.build().send().await,
+        }
+    }
+
+    /// Normalizes a bucket id before it's pushed into the url, see `normalize_bucket_id`.
+    ///
+    /// An invalid id is kept as it is and the error is returned by every `Executor` method
+    /// instead of sending or building the request.
+    pub(crate) fn bucket_id<'a>(&mut self, bucket_id: &'a str) -> &'a str {
+        match normalize_bucket_id(bucket_id) {
+            Ok(id) => id,
+            Err(error) => {
+                self.invalid_request.get_or_insert(error.into());
+                bucket_id
+            }
+        }
+    }
+
+    /// Sets the `Content-Disposition` header, see `content_disposition_value`.
+    ///
+    /// An invalid value is returned by every `Executor` method, like an invalid bucket id.
+    pub(crate) fn content_disposition(&mut self, content_disposition: &str) {
+        match content_disposition_value(content_disposition) {
+            Ok(value) => {
+                self.headers
+                    .lock()
+                    .unwrap()
+                    .insert(CONTENT_DISPOSITION, value);
+            }
+            Err(error) => {
+                self.invalid_request.get_or_insert(error);
+            }
+        }
+    }
+
+    /// Sets the `Authorization` header with a fresh token of the token provider, trimmed of
+    /// surrounding whitespace like a trailing newline.
+    ///
+    /// A token which is still no valid header value is returned by every `Executor` method,
+    /// like an invalid bucket id.
+    pub(crate) fn apply_token_provider(&mut self) {
+        if let Some(token_provider) = self.token_provider.take() {
+            match HeaderValue::from_str(&format!("Bearer {}", token_provider().trim())) {
+                Ok(value) => {
+                    self.headers.lock().unwrap().insert(AUTHORIZATION, value);
+                }
+                Err(_) => {
+                    self.invalid_request
+                        .get_or_insert(invalid_header(AUTHORIZATION.as_str()));
+                }
+            }
+        }
+    }
+
+    /// Takes the error found while building the request, e.g. an invalid bucket id.
+    pub(crate) fn take_invalid_request(&mut self) -> Option<errors::Error> {
+        self.invalid_request.take()
+    }
+
+    /// Creates a new `Builder` sharing the same client, starting from a copy of the current url and headers.
+    pub(crate) fn fork(&self) -> Self {
+        let mut builder = Self::new(
+            self.url.clone(),
+            Arc::new(Mutex::new(self.headers.lock().unwrap().clone())),
+            self.client.clone(),
+        );
+        builder.backend = self.backend.clone();
+        builder.compress_body = self.compress_body;
+        builder.default_file_options = self.default_file_options.clone();
+        builder.token_provider = self.token_provider.clone();
+        builder.token_param = self.token_param.clone();
+        builder.base_path = self.base_path.clone();
+        builder.pretty_json = self.pretty_json;
+        builder.invalid_request = self.invalid_request.clone();
+        builder.max_error_body_size = self.max_error_body_size;
+        builder
     }
 
     /// Creates a new `Executor` instance based on the current `Builder` configuration.
@@ -164,40 +701,273 @@ impl Builder {
     }
 }
 
+/// The error of a value which can't be sent in the `name` header, the value is left out
+/// since it may be a credential.
+fn invalid_header(name: &str) -> errors::Error {
+    errors::Error {
+        status_code: "400".to_string(),
+        error: "InvalidHeader".to_string(),
+        message: format!("the {} header value is invalid", name),
+        context: None,
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use flate2::read::GzDecoder;
     use reqwest::{
         header::{HeaderMap, HeaderValue},
-        Client,
+        Client, Method,
+    };
+    use std::{
+        collections::HashMap,
+        io::Read,
+        sync::{Arc, Mutex},
     };
-    use std::sync::{Arc, Mutex};
     use url::Url;
 
-    use super::Builder;
+    use super::{BodyType, Builder};
+    use crate::{testing::MockBackend, Storage};
 
     #[test]
     fn test_create_builder() {
         let mut headers = HeaderMap::new();
         headers.insert("Authorization", HeaderValue::from_static("Bearer test"));
         let url = Url::parse("http://localhost").unwrap();
-        let builder = Builder::new(
-            url,
-            Arc::new(Mutex::new(headers)),
-            Arc::new(Mutex::new(Client::new())),
-        );
+        let builder = Builder::new(url, Arc::new(Mutex::new(headers)), Client::new());
         assert_eq!(builder.url.scheme(), "http");
         assert_eq!(builder.headers.lock().unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_clear_body_and_headers_extra() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", HeaderValue::from_static("Bearer test"));
+        headers.insert("apikey", HeaderValue::from_static("test"));
+        let mut builder = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(headers)),
+            Client::new(),
+        )
+        .header("x-upsert", HeaderValue::from_static("true"));
+        builder.body = Some(BodyType::StringBody("body".to_string()));
+
+        builder.clear_body();
+        builder.clear_headers_extra();
+
+        assert!(builder.body.is_none());
+        let headers = builder.headers.lock().unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers.get("Authorization").unwrap(), "Bearer test");
+        assert_eq!(headers.get("apikey").unwrap(), "test");
+    }
+
     #[test]
     fn test_add_header() {
         let url = Url::parse("http://localhost").unwrap();
+        let builder = Builder::new(url, Arc::new(Mutex::new(HeaderMap::new())), Client::new())
+            .header("Authorization", HeaderValue::from_static("Bearer test"));
+        assert_eq!(builder.headers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_body_json() {
         let builder = Builder::new(
-            url,
+            Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
-        .header("Authorization", HeaderValue::from_static("Bearer test"));
-        assert_eq!(builder.headers.lock().unwrap().len(), 1);
+        .body_json(&serde_json::json!({ "name": "thefux" }))
+        .unwrap();
+
+        assert_eq!(
+            builder.headers.lock().unwrap().get("Content-Type").unwrap(),
+            "application/json"
+        );
+        match builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"name":"thefux"}"#),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_text_body() {
+        let builder = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .text_body("name,size\nbtc.pdf,184292".to_string(), "text/csv")
+        .unwrap();
+
+        assert_eq!(
+            builder.headers.lock().unwrap().get("Content-Type").unwrap(),
+            "text/csv"
+        );
+        match builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, "name,size\nbtc.pdf,184292"),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_text_body_invalid_content_type() {
+        let result = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .text_body("{}".to_string(), "text/csv\r\n");
+
+        assert_eq!(result.err().unwrap().error, "InvalidContentType");
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        let request = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .idempotency_key("key")
+        .build()
+        .build()
+        .unwrap();
+
+        assert_eq!(request.headers().get("Idempotency-Key").unwrap(), "key");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_idempotency_key() {
+        let backend = Arc::new(MockBackend::new());
+
+        let error = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .idempotency_key("key\r\n")
+            .get_bucket_details("thefux")
+            .execute()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.error, "InvalidHeader");
+        assert!(backend.requests().is_empty());
+    }
+
+    #[test]
+    fn test_pretty_json() {
+        let builder = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .pretty_json()
+        .body_json(&serde_json::json!({ "file_size_limit": 536870912000u64 }))
+        .unwrap();
+
+        match builder.body {
+            Some(BodyType::StringBody(val)) => {
+                assert_eq!(val, "{\n  \"file_size_limit\": 536870912000\n}")
+            }
+            _ => panic!("nop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_metadata() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/btc.pdf"}"#);
+        let metadata = HashMap::from([
+            ("label".to_string(), "invoice".to_string()),
+            ("year".to_string(), "2023".to_string()),
+        ]);
+
+        Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .user_metadata(&metadata)
+            .update_object_async("thefux", "btc.pdf", "out/test.pdf")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let request = &backend.requests()[0];
+        assert_eq!(request.method, Method::PUT);
+        assert_eq!(request.url.path(), "/object/thefux/btc.pdf");
+        let header = request.headers.get("x-metadata").unwrap().to_str().unwrap();
+        let sent: HashMap<String, String> =
+            serde_json::from_slice(&STANDARD.decode(header).unwrap()).unwrap();
+        assert_eq!(sent, metadata);
+    }
+
+    #[test]
+    fn test_path_replaces_segments() {
+        let builder = Builder::new(
+            Url::parse("http://localhost/storage/v1").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        );
+        let segments = ["object", "public", "thefux", "a b.pdf"];
+
+        let builder = builder.path(&["bucket"]).path(&segments);
+        assert_eq!(
+            builder.url.path(),
+            format!("/storage/v1/{}", segments.join("/").replace(' ', "%20"))
+        );
+
+        let builder = builder.path(&[]);
+        assert_eq!(builder.url.path(), "/storage/v1");
+    }
+
+    #[test]
+    fn test_method() {
+        let builder = Builder::new(
+            Url::parse("http://localhost/storage/v1").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .method(Method::PATCH)
+        .text_body("{}".to_string(), "application/json")
+        .unwrap();
+
+        assert_eq!(builder.method, Method::PATCH);
+        let request = builder.build().build().unwrap();
+        assert_eq!(request.method(), Method::PATCH);
+    }
+
+    #[test]
+    fn test_raw_request() {
+        let executor = Builder::new(
+            Url::parse("http://localhost/").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .raw_request(Method::DELETE, &["bucket", "thefux"]);
+
+        assert_eq!(executor.method(), Method::DELETE);
+        assert_eq!(executor.url().path(), "/bucket/thefux");
+    }
+
+    #[test]
+    fn test_compress_string_body() {
+        let mut builder = Builder::new(
+            Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        );
+        builder.compress_body = true;
+        builder.body = Some(BodyType::StringBody(r#"{"name":"thefux"}"#.to_string()));
+
+        let request = builder.build().build().unwrap();
+        assert_eq!(request.headers().get("Content-Encoding").unwrap(), "gzip");
+
+        let mut decoded = String::new();
+        GzDecoder::new(request.body().unwrap().as_bytes().unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, r#"{"name":"thefux"}"#);
     }
 }
@@ -1,9 +1,140 @@
-use crate::model::errors;
-use reqwest::{Error, Response, StatusCode};
+use crate::model::{
+    errors,
+    object::{Either, ObjectWithMeta},
+    request::RequestParts,
+};
+use flate2::write::GzDecoder;
+use futures::TryStreamExt;
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, ETAG, LAST_MODIFIED,
+    },
+    Error, Method, Response, StatusCode,
+};
 use serde::Deserialize;
+use std::io::{self, Write};
+use tokio::io::AsyncRead;
+use tokio_util::io::StreamReader;
+use url::Url;
 
 use super::builder::Builder;
 
+/// Parses the body of a failed request into an `errors::Error`,
+/// bodies which are not an error json are kept as message.
+pub(crate) fn error_from_body(status: StatusCode, text: String) -> errors::Error {
+    serde_json::from_str(&text).unwrap_or_else(|_| errors::Error {
+        status_code: status.as_str().to_string(),
+        error: status.canonical_reason().unwrap_or_default().to_string(),
+        message: text,
+        context: None,
+    })
+}
+
+/// Reads the body of a failed response into an `errors::Error`.
+///
+/// Gateways may gzip error bodies even when the client didn't ask for it,
+/// such bodies are decompressed before parsing them. At most `limit` bytes of the decoded
+/// body are kept, see `Builder::max_error_body_size`.
+pub(crate) async fn error_from_response(
+    response: Response,
+    limit: usize,
+) -> Result<errors::Error, Error> {
+    let (status, text) = error_text(response, limit).await?;
+    Ok(error_from_body(status, text))
+}
+
+/// Collects at most `limit` bytes, the bytes beyond are dropped and mark the output as truncated.
+struct LimitedWriter {
+    bytes: Vec<u8>,
+    limit: usize,
+    truncated: bool,
+}
+
+impl LimitedWriter {
+    fn new(limit: usize) -> Self {
+        Self {
+            bytes: Vec::new(),
+            limit,
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, buf: &[u8]) {
+        let room = self.limit - self.bytes.len();
+        if buf.len() > room {
+            self.truncated = true;
+        }
+        self.bytes.extend_from_slice(&buf[..buf.len().min(room)]);
+    }
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the body of a failed response as text, decompressing gzipped bodies.
+///
+/// Bodies longer than `limit` bytes are cut and a note is appended, the remaining chunks
+/// aren't read. Gzipped bodies are cut after decoding them, a gzipped body which can't be
+/// decoded is replaced by a placeholder instead of showing the compressed bytes.
+async fn error_text(mut response: Response, limit: usize) -> Result<(StatusCode, String), Error> {
+    let status = response.status();
+    let mut raw = LimitedWriter::new(limit);
+    let mut decoder: Option<GzDecoder<LimitedWriter>> = None;
+    let mut undecodable = false;
+    while let Some(chunk) = response.chunk().await? {
+        if decoder.is_none() && raw.bytes.is_empty() && chunk.starts_with(&[0x1f, 0x8b]) {
+            decoder = Some(GzDecoder::new(LimitedWriter::new(limit)));
+        }
+        let output = match decoder.as_mut() {
+            Some(decoder) => {
+                if decoder.write_all(&chunk).is_err() {
+                    undecodable = true;
+                    break;
+                }
+                decoder.get_ref()
+            }
+            None => {
+                raw.push(&chunk);
+                &raw
+            }
+        };
+        if output.truncated {
+            break;
+        }
+    }
+
+    let output = match decoder.as_mut() {
+        Some(decoder) => {
+            // a cut stream can't be finished, only complete streams are checked
+            if !decoder.get_ref().truncated && decoder.try_finish().is_err() {
+                undecodable = true;
+            }
+            decoder.get_ref()
+        }
+        None => &raw,
+    };
+
+    if undecodable {
+        return Ok((
+            status,
+            "the gzipped error body could not be decoded".to_string(),
+        ));
+    }
+    let mut text = String::from_utf8_lossy(&output.bytes).to_string();
+    if output.truncated {
+        text.push_str(&format!(" (truncated to {} bytes)", limit));
+    }
+    Ok((status, text))
+}
+
 pub struct Executor {
     pub builder: Builder,
 }
@@ -21,11 +152,105 @@ impl Executor {
         Self { builder }
     }
 
+    /// The url the request will be sent to.
+    ///
+    /// This is part of the stable api and can be used to inspect the request before sending it.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .get_object("thefux", "btc.pdf");
+    /// assert_eq!(executor.url().path(), "/storage/v1/object/thefux/btc.pdf");
+    /// ```
+    pub fn url(&self) -> &Url {
+        &self.builder.url
+    }
+
+    /// The HTTP method the request will be sent with.
+    ///
+    /// This is part of the stable api and can be used to inspect the request before sending it.
+    ///
+    /// # Example
+    /// ```
+    /// use reqwest::Method;
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .get_object("thefux", "btc.pdf");
+    /// assert_eq!(executor.method(), Method::GET);
+    /// ```
+    pub fn method(&self) -> &Method {
+        &self.builder.method
+    }
+
+    /// Builds the request the same way `execute` does, without sending it.
+    ///
+    /// The values of the `Authorization` and `apikey` headers are redacted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RequestParts, errors::Error>` - The description of the request, or the error
+    ///   found while building it, e.g. an invalid bucket id.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let parts = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .get_object("thefux", "btc.pdf")
+    ///     .dry_run()
+    ///     .unwrap();
+    /// assert_eq!(parts.method, "GET");
+    /// assert_eq!(parts.url, "http://localhost/storage/v1/object/thefux/btc.pdf");
+    /// ```
+    pub fn dry_run(mut self) -> Result<RequestParts, errors::Error> {
+        self.check_request()?;
+        let request = self.builder.build().build()?;
+
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if name == AUTHORIZATION || name.as_str() == "apikey" {
+                    "<redacted>".to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).to_string()
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+
+        let body_summary = request.body().map(|body| match body.as_bytes() {
+            Some(bytes) if request.headers().contains_key(CONTENT_ENCODING) => {
+                format!("<{} encoded bytes>", bytes.len())
+            }
+            Some(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => format!("<{} bytes>", bytes.len()),
+            },
+            None => "<stream>".to_string(),
+        });
+
+        Ok(RequestParts {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers,
+            body_summary,
+        })
+    }
+
     /// Executes the constructed HTTP request and returns the response as a `Result`.
     ///
     /// # Returns
     ///
-    /// * `Result<Response, Error>` - The result of the executed request.
+    /// * `Result<Response, errors::Error>` - The result of the executed request, non-2xx
+    ///   responses are returned as they are. Requests with an error found while building them,
+    ///   e.g. an invalid bucket id, aren't sent.
     ///
     /// # Example
     ///
@@ -55,12 +280,205 @@ impl Executor {
     ///     println!("{:?}", response);
     /// }
     /// ```
-    pub async fn execute(self) -> Result<Response, Error> {
-        self.builder.build().send().await
+    pub async fn execute(self) -> Result<Response, errors::Error> {
+        self.send().await
+    }
+
+    /// Sends the request unless an error was found while building it.
+    async fn send(mut self) -> Result<Response, errors::Error> {
+        self.check_request()?;
+        Ok(self.builder.run().await?)
+    }
+
+    /// Executes the constructed HTTP request, returning an error for non-2xx responses.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Response, errors::Error>` - The successful response, or the error parsed from the body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     match storage.from().get_object("thefux", "btc.pdf").execute_checked().await {
+    ///         Ok(response) => println!("{:?}", response.bytes().await),
+    ///         Err(error) => println!("{:?}", error.kind()),
+    ///     }
+    /// }
+    /// ```
+    pub async fn execute_checked(self) -> Result<Response, errors::Error> {
+        let context = self.context();
+        self.execute_checked_intern().await.map_err(context)
+    }
+
+    async fn execute_checked_intern(self) -> Result<Response, errors::Error> {
+        let limit = self.builder.max_error_body_size;
+        let response = self.send().await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(error_from_response(response, limit).await?)
+        }
+    }
+
+    /// Executes the constructed HTTP request and returns the body along with the `ETag`,
+    /// `Last-Modified` and `Content-Type` headers of the response.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ObjectWithMeta, errors::Error>` - The body and metadata of the successful
+    ///   response, or the error parsed from the body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let object = storage
+    ///         .from()
+    ///         .get_object("thefux", "btc.pdf")
+    ///         .execute_with_meta()
+    ///         .await;
+    ///
+    ///     println!("{:?}", object);
+    /// }
+    /// ```
+    pub async fn execute_with_meta(self) -> Result<ObjectWithMeta, errors::Error> {
+        let context = self.context();
+        self.execute_with_meta_intern().await.map_err(context)
+    }
+
+    async fn execute_with_meta_intern(self) -> Result<ObjectWithMeta, errors::Error> {
+        fn header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        }
+
+        let response = self.execute_checked_intern().await?;
+        let headers = response.headers().clone();
+
+        Ok(ObjectWithMeta {
+            body: response.bytes().await?,
+            etag: header(&headers, ETAG),
+            last_modified: header(&headers, LAST_MODIFIED),
+            content_type: header(&headers, CONTENT_TYPE),
+        })
+    }
+
+    /// Executes the constructed HTTP request and returns the response along with the id the
+    /// server assigned to the request, from the `x-request-id` or `sb-gateway-request-id`
+    /// header. Log it to trace a call when contacting support.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Response, Option<String>), errors::Error>` - The response and the request id,
+    ///   if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let (response, request_id) = storage
+    ///         .from()
+    ///         .get_bucket_details("thefux")
+    ///         .execute_with_request_id()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     println!("{:?} {:?}", request_id, response.status());
+    /// }
+    /// ```
+    pub async fn execute_with_request_id(
+        self,
+    ) -> Result<(Response, Option<String>), errors::Error> {
+        let response = self.send().await?;
+        let request_id = ["x-request-id", "sb-gateway-request-id"]
+            .iter()
+            .find_map(|name| response.headers().get(*name))
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        Ok((response, request_id))
+    }
+
+    /// Executes the constructed HTTP request and exposes the response body as `AsyncRead`,
+    /// the body is streamed without being buffered.
+    ///
+    /// The reader can be passed to `Builder::upload_object_stream`, which wraps it into a
+    /// `BodyType::ReqwestBody`, to re-upload a download chunk by chunk, e.g. an image rendered
+    /// by `get_object_with_transform`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl AsyncRead, errors::Error>` - The body of the successful response,
+    ///   or the error parsed from the body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let mut reader = storage
+    ///         .from()
+    ///         .get_object("thefux", "btc.pdf")
+    ///         .execute_reader()
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let mut file = tokio::fs::File::create("btc.pdf").await.unwrap();
+    ///     tokio::io::copy(&mut reader, &mut file).await.unwrap();
+    /// }
+    /// ```
+    pub async fn execute_reader(self) -> Result<impl AsyncRead + Unpin, errors::Error> {
+        let response = self.execute_checked().await?;
+        Ok(StreamReader::new(
+            response.bytes_stream().map_err(std::io::Error::other),
+        ))
     }
 
     /// Executes the constructed HTTP request and deserializes the response body into a generic struct.
     ///
+    /// Errors carry the failed operation as `METHOD /path` in their `context` field.
+    ///
     /// # Returns
     ///
     /// * `Result<T, errors::Error>` - The result of deserializing the response body into the provided generic struct.
@@ -94,20 +512,641 @@ impl Executor {
     where
         T: for<'de> Deserialize<'de>,
     {
-        let response = self.builder.build().send().await.unwrap();
+        let context = self.context();
+        self.execute_from_with_error::<T, errors::Error>()
+            .await
+            .map_err(context)
+    }
+
+    /// Returns the error found while building the request, e.g. an invalid bucket id.
+    fn check_request(&mut self) -> Result<(), errors::Error> {
+        self.builder.apply_token_provider();
+        match self.builder.take_invalid_request() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns a closure attaching the method and path of the request to an error.
+    fn context(&self) -> impl FnOnce(errors::Error) -> errors::Error {
+        // the query is left out on purpose, it may contain signed url tokens
+        let context = format!("{} {}", self.builder.method, self.builder.url.path());
+        move |mut error| {
+            error.context = Some(context);
+            error
+        }
+    }
+
+    /// Executes the constructed HTTP request and deserializes the response body into a generic
+    /// struct, error bodies are deserialized into the generic error type `E`.
+    ///
+    /// Error bodies which don't match `E`, transport errors and invalid success bodies are
+    /// converted from `errors::Error`. `execute_from::<T>()` is the same as
+    /// `execute_from_with_error::<T, errors::Error>()`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, E>` - The deserialized response body, or the deserialized error body.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::{bucket::BucketDetails, errors},
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct GatewayError {
+    ///     code: u16,
+    ///     reason: String,
+    /// }
+    ///
+    /// impl From<errors::Error> for GatewayError {
+    ///     fn from(error: errors::Error) -> Self {
+    ///         Self {
+    ///             code: error.status_code.parse().unwrap_or_default(),
+    ///             reason: error.message,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_bucket_details("thefux")
+    ///         .execute_from_with_error::<BucketDetails, GatewayError>()
+    ///         .await;
+    ///
+    ///     println!("{:?}", response);
+    /// }
+    /// ```
+    pub async fn execute_from_with_error<T, E>(self) -> Result<T, E>
+    where
+        T: for<'de> Deserialize<'de>,
+        E: for<'de> Deserialize<'de> + From<errors::Error>,
+    {
+        let limit = self.builder.max_error_body_size;
+        let response = self.send().await.map_err(E::from)?;
         let status = response.status();
 
-        let text = response.text().await.unwrap();
+        if !status.is_success() {
+            let (status, text) = error_text(response, limit)
+                .await
+                .map_err(|error| E::from(error.into()))?;
+            return Err(serde_json::from_str(&text)
+                .unwrap_or_else(|_| E::from(error_from_body(status, text))));
+        }
 
-        match status {
-            StatusCode::OK => {
-                if let Ok(result) = serde_json::from_str(&text) {
-                    Ok(result)
-                } else {
-                    Err(serde_json::from_str(&text).unwrap())
+        let text = response
+            .text()
+            .await
+            .map_err(|error| E::from(error.into()))?;
+        // an empty body deserializes like `null`, e.g. into `()` or an `Option`
+        let body = if text.trim().is_empty() {
+            "null"
+        } else {
+            &text
+        };
+        serde_json::from_str(body).map_err(|error| {
+            E::from(errors::Error {
+                status_code: status.as_str().to_string(),
+                error: "InvalidResponse".to_string(),
+                message: error.to_string(),
+                context: None,
+            })
+        })
+    }
+
+    /// Executes the constructed HTTP request, deserializes `application/json` responses into a
+    /// generic struct and returns any other response as raw bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Either<T>, errors::Error>` - The parsed json or the raw bytes of the response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::object::{Either, ObjectInfo},
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     match storage
+    ///         .from()
+    ///         .get_object("thefux", "btc.pdf")
+    ///         .execute_auto::<ObjectInfo>()
+    ///         .await
+    ///     {
+    ///         Ok(Either::Json(info)) => println!("{:?}", info),
+    ///         Ok(Either::Bytes(bytes)) => println!("{} bytes", bytes.len()),
+    ///         Err(error) => println!("{:?}", error),
+    ///     }
+    /// }
+    /// ```
+    pub async fn execute_auto<T>(self) -> Result<Either<T>, errors::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let context = self.context();
+        self.execute_auto_intern().await.map_err(context)
+    }
+
+    async fn execute_auto_intern<T>(self) -> Result<Either<T>, errors::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let limit = self.builder.max_error_body_size;
+        let response = self.send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(error_from_response(response, limit).await?);
+        }
+
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<mime_guess::mime::Mime>().ok())
+            .is_some_and(|mime| {
+                mime.subtype() == mime_guess::mime::JSON
+                    || mime.suffix() == Some(mime_guess::mime::JSON)
+            });
+
+        let bytes = response.bytes().await?;
+        if is_json {
+            serde_json::from_slice(&bytes)
+                .map(Either::Json)
+                .map_err(|error| errors::Error {
+                    status_code: status.as_str().to_string(),
+                    error: "InvalidResponse".to_string(),
+                    message: error.to_string(),
+                    context: None,
+                })
+        } else {
+            Ok(Either::Bytes(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use flate2::{write::GzEncoder, Compression};
+    use reqwest::{header::HeaderMap, Client, Method};
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use serde::Deserialize;
+    use tokio::io::AsyncReadExt;
+
+    use crate::{
+        build::builder::Builder,
+        model::{
+            errors::{self, ErrorKind},
+            object::{Either, Response},
+        },
+        testing::MockBackend,
+        Storage,
+    };
+
+    fn storage(backend: &Arc<MockBackend>) -> Storage {
+        Storage::new("http://localhost").with_backend(backend.clone())
+    }
+
+    #[tokio::test]
+    async fn test_execute_reader() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], "hello world");
+
+        let mut reader = storage(&backend)
+            .from()
+            .get_object("thefux", "hello.txt")
+            .execute_reader()
+            .await
+            .unwrap();
+
+        let mut buffer = [0; 5];
+        reader.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"hello");
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, " world");
+    }
+
+    #[tokio::test]
+    async fn test_execute_reader_error() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            404,
+            &[],
+            r#"{"statusCode":"404","error":"not_found","message":"Object not found"}"#,
+        );
+
+        let error = storage(&backend)
+            .from()
+            .get_object("thefux", "hello.txt")
+            .execute_reader()
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_inspect_url_and_method() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .get_object("thefux", "btc.pdf");
+
+        assert_eq!(
+            executor.url().as_str(),
+            "http://localhost/object/thefux/btc.pdf"
+        );
+        assert_eq!(executor.method(), Method::GET);
+    }
+
+    #[test]
+    fn test_dry_run_create_bucket() {
+        let parts = Storage::from_project_ref("abcd1234", "secret")
+            .from()
+            .create_bucket(r#"{"name":"thefux"}"#)
+            .dry_run()
+            .unwrap();
+
+        assert_eq!(parts.method, "POST");
+        assert_eq!(parts.url, "https://abcd1234.supabase.co/storage/v1/bucket");
+        assert_eq!(parts.headers["content-type"], "application/json");
+        assert_eq!(parts.headers["authorization"], "<redacted>");
+        assert_eq!(parts.headers["apikey"], "<redacted>");
+        assert_eq!(parts.body_summary.as_deref(), Some(r#"{"name":"thefux"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_move_object_response() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully moved"}"#);
+
+        let response = storage(&backend)
+            .from()
+            .move_object("thefux", "from", "to")
+            .execute_from::<Response>()
+            .await
+            .unwrap();
+
+        assert_eq!(response.message, "Successfully moved");
+    }
+
+    #[tokio::test]
+    async fn test_copy_object_response() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully copied"}"#);
+
+        let response = storage(&backend)
+            .from()
+            .copy_object("thefux", "from", "to")
+            .execute_from::<Response>()
+            .await
+            .unwrap();
+
+        assert_eq!(response.message, "Successfully copied");
+    }
+
+    #[tokio::test]
+    async fn test_execute_from_empty_body() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], "");
+
+        let response = storage(&backend)
+            .from()
+            .delete_object("thefux", "btc.pdf")
+            .execute_from::<Option<Response>>()
+            .await
+            .unwrap();
+
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_execute_from_error() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            404,
+            &[],
+            r#"{"statusCode":"404","error":"not_found","message":"Object not found"}"#,
+        );
+        backend.push_response(502, &[], "Bad Gateway");
+
+        let error = storage(&backend)
+            .from()
+            .move_object("thefux", "from", "to")
+            .execute_from::<Response>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+
+        let error = storage(&backend)
+            .from()
+            .move_object("thefux", "from", "to")
+            .execute_from::<Response>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.status_code, "502");
+        assert_eq!(error.message, "Bad Gateway");
+    }
+
+    #[tokio::test]
+    async fn test_execute_from_error_context() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            400,
+            &[],
+            r#"{"statusCode":"400","error":"InvalidJWT","message":"jwt expired"}"#,
+        );
+
+        let error = storage(&backend)
+            .from()
+            .get_object_with_pre_assigned_url("thefux", "btc.pdf", "secret")
+            .execute_from::<Response>()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.context.as_deref(),
+            Some("GET /object/sign/thefux/btc.pdf")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_from_gzipped_error() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(br#"{"statusCode":"404","error":"not_found","message":"Object not found"}"#)
+            .unwrap();
+
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            404,
+            &[("content-encoding", "gzip")],
+            encoder.finish().unwrap(),
+        );
+
+        let error = storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_from::<Response>()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(error.message, "Object not found");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_error_body_truncated() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(500, &[], "x".repeat(4096));
+
+        let error = storage(&backend)
+            .from()
+            .max_error_body_size(16)
+            .get_object("thefux", "btc.pdf")
+            .execute_checked()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code, "500");
+        assert_eq!(error.message, "xxxxxxxxxxxxxxxx (truncated to 16 bytes)");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_gzipped_error_body_truncated() {
+        let body: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let body = body.join(",");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        // the limit is applied to the decoded body, not to the compressed one
+        assert!(compressed.len() > 64);
+
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(500, &[("content-encoding", "gzip")], compressed.clone());
+        backend.push_response(500, &[("content-encoding", "gzip")], &compressed[..20]);
+
+        let error = storage(&backend)
+            .from()
+            .max_error_body_size(64)
+            .get_object("thefux", "btc.pdf")
+            .execute_checked()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.message,
+            format!("{} (truncated to 64 bytes)", &body[..64])
+        );
+
+        let error = storage(&backend)
+            .from()
+            .max_error_body_size(64)
+            .get_object("thefux", "btc.pdf")
+            .execute_checked()
+            .await
+            .unwrap_err();
+        assert_eq!(error.message, "the gzipped error body could not be decoded");
+    }
+
+    #[tokio::test]
+    async fn test_execute_checked() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], "pdf");
+        backend.push_response(
+            404,
+            &[],
+            r#"{"statusCode":"404","error":"not_found","message":"Object not found"}"#,
+        );
+
+        let response = storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_checked()
+            .await
+            .unwrap();
+        assert_eq!(response.text().await.unwrap(), "pdf");
+
+        let error = storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_checked()
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(error.context.as_deref(), Some("GET /object/thefux/btc.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_from_with_custom_error() {
+        #[derive(Debug, Deserialize)]
+        struct GatewayError {
+            code: u16,
+            reason: String,
+        }
+
+        impl From<errors::Error> for GatewayError {
+            fn from(error: errors::Error) -> Self {
+                Self {
+                    code: error.status_code.parse().unwrap_or_default(),
+                    reason: error.message,
                 }
             }
-            _ => Err(serde_json::from_str(&text).unwrap()),
+        }
+
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(429, &[], r#"{"code":429,"reason":"slow down"}"#);
+        backend.push_response(502, &[], "Bad Gateway");
+
+        let error = storage(&backend)
+            .from()
+            .get_bucket_details("thefux")
+            .execute_from_with_error::<Response, GatewayError>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, 429);
+        assert_eq!(error.reason, "slow down");
+
+        let error = storage(&backend)
+            .from()
+            .get_bucket_details("thefux")
+            .execute_from_with_error::<Response, GatewayError>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, 502);
+        assert_eq!(error.reason, "Bad Gateway");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_meta() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[
+                ("etag", "\"abc\""),
+                ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("content-type", "application/pdf"),
+            ],
+            "pdf",
+        );
+
+        let object = storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_with_meta()
+            .await
+            .unwrap();
+
+        assert_eq!(object.body, "pdf");
+        assert_eq!(object.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(
+            object.last_modified.as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        assert_eq!(object.content_type.as_deref(), Some("application/pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_meta_error() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            404,
+            &[("etag", "\"abc\"")],
+            r#"{"statusCode":"404","error":"not_found","message":"Object not found"}"#,
+        );
+
+        let error = storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_with_meta()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(error.context.as_deref(), Some("GET /object/thefux/btc.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_request_id() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[("x-request-id", "req-1")], "{}");
+        backend.push_response(200, &[("sb-gateway-request-id", "gw-2")], "{}");
+        backend.push_response(200, &[], "{}");
+
+        let mut request_ids = Vec::new();
+        for _ in 0..3 {
+            let (response, request_id) = storage(&backend)
+                .from()
+                .get_bucket_details("thefux")
+                .execute_with_request_id()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200);
+            request_ids.push(request_id);
+        }
+
+        assert_eq!(
+            request_ids,
+            vec![Some("req-1".to_string()), Some("gw-2".to_string()), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_auto() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[("content-type", "application/json; charset=utf-8")],
+            r#"{"message":"ok"}"#,
+        );
+        backend.push_response(200, &[("content-type", "application/pdf")], "pdf");
+
+        match storage(&backend)
+            .from()
+            .get_object("thefux", "btc.json")
+            .execute_auto::<Response>()
+            .await
+            .unwrap()
+        {
+            Either::Json(response) => assert_eq!(response.message, "ok"),
+            Either::Bytes(_) => panic!("expected json"),
+        }
+
+        match storage(&backend)
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute_auto::<Response>()
+            .await
+            .unwrap()
+        {
+            Either::Bytes(bytes) => assert_eq!(bytes, "pdf"),
+            Either::Json(_) => panic!("expected bytes"),
         }
     }
 }
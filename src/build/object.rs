@@ -1,25 +1,46 @@
+pub mod batch;
+pub mod guard;
 pub mod list;
 pub mod move_copy;
+pub mod progress;
 pub mod public;
 pub mod render;
+pub mod resumable;
+pub mod s3;
 pub mod sign;
 pub mod upload;
 
-pub use super::object::list::*;
-pub use super::object::move_copy::*;
-pub use super::object::public::*;
-pub use super::object::render::*;
-pub use super::object::sign::*;
-pub use super::object::upload::*;
+use std::{io, path::Path};
 
-use reqwest::{header::HeaderValue, Body, Method};
-use tokio::fs::File;
+use reqwest::{
+    header::{HeaderValue, ACCEPT_RANGES, CONTENT_TYPE},
+    Body, Method,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt},
+};
 use tokio_util::codec::{BytesCodec, FramedRead};
 
-use crate::build::builder::BodyType;
+use crate::{
+    build::builder::{object_segments, BodyType},
+    model::{
+        bucket::BucketDetails,
+        errors::{self, ErrorKind},
+        object::{DeletePrefixResult, ObjectMetadata, UploadResult},
+        options::{cache_control_value, FileOptions, ListObjectsOptions, Transform},
+    },
+};
 
 use super::{builder::Builder, executor::Executor};
 
+/// number of objects requested per page when listing objects
+pub(crate) const LIST_PAGE_SIZE: u32 = 100;
+/// maximum number of objects deleted per request
+pub(crate) const DELETE_BATCH_SIZE: usize = 1000;
+
 impl Builder {
     fn delete_object_intern(mut self) -> Executor {
         self.method = Method::DELETE;
@@ -59,12 +80,13 @@ impl Builder {
     /// }
     /// ```
     pub fn delete_object(mut self, bucket_id: &str, object: &str) -> Executor {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push(bucket_id)
-            .push(object);
+            .extend(object_segments(object));
         self.delete_object_intern()
     }
 
@@ -84,7 +106,7 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
+    ///     model::object::DeletedObject,
     /// };
     /// use dotenv::dotenv;
     ///
@@ -95,7 +117,7 @@ impl Builder {
     ///     let response = Storage::new_with_config(config)
     ///         .from()
     ///         .delete_objects("thefux", r#"{ "prefixes" : [ "file_name.pdf" ]}"#)
-    ///         .execute()
+    ///         .execute_from::<Vec<DeletedObject>>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -105,6 +127,7 @@ impl Builder {
             .lock()
             .unwrap()
             .insert("Content-Type", HeaderValue::from_static("application/json"));
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -114,6 +137,109 @@ impl Builder {
         self.delete_object_intern()
     }
 
+    /// lists the paths of all objects under the prefix, descending into folders
+    pub(crate) async fn list_prefix_paths(
+        &self,
+        bucket_id: &str,
+        prefix: &str,
+    ) -> Result<Vec<String>, errors::Error> {
+        let mut paths = vec![];
+        let mut folders = vec![prefix.trim_matches('/').to_string()];
+
+        while let Some(folder) = folders.pop() {
+            let mut offset = 0;
+            loop {
+                let page = self
+                    .fork()
+                    .list_objects_from(
+                        bucket_id,
+                        ListObjectsOptions {
+                            limit: Some(LIST_PAGE_SIZE),
+                            offset: Some(offset),
+                            ..ListObjectsOptions::new(&folder)
+                        },
+                    )
+                    .execute_from::<Vec<ObjectMetadata>>()
+                    .await?;
+
+                for entry in &page {
+                    let path = if folder.is_empty() {
+                        entry.name.clone()
+                    } else {
+                        format!("{}/{}", folder, entry.name)
+                    };
+                    match entry.id {
+                        Some(_) => paths.push(path),
+                        None => folders.push(path),
+                    }
+                }
+
+                if page.len() < LIST_PAGE_SIZE as usize {
+                    break;
+                }
+                offset += LIST_PAGE_SIZE;
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// delete all objects under a prefix, including the objects of nested folders
+    ///
+    /// The objects are listed page by page and deleted in batches of at most 1000 objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `prefix` - the folder to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Result<DeletePrefixResult, errors::Error>` - The number of deleted objects and the failed batches,
+    ///   or the error if listing the objects failed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let result = Storage::new_with_config(config)
+    ///         .from()
+    ///         .delete_prefix("thefux", "invoices/2023")
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn delete_prefix(
+        self,
+        bucket_id: &str,
+        prefix: &str,
+    ) -> Result<DeletePrefixResult, errors::Error> {
+        let paths = self.list_prefix_paths(bucket_id, prefix).await?;
+
+        let mut result = DeletePrefixResult::default();
+        for batch in paths.chunks(DELETE_BATCH_SIZE) {
+            let body = serde_json::json!({ "prefixes": batch }).to_string();
+            match self
+                .fork()
+                .delete_objects(bucket_id, &body)
+                .execute_from::<Vec<serde_json::Value>>()
+                .await
+            {
+                Ok(deleted) => result.deleted += deleted.len(),
+                Err(error) => result.failures.push(error),
+            }
+        }
+
+        Ok(result)
+    }
+
     /// get an object from the storage
     ///
     /// # Arguments
@@ -147,16 +273,198 @@ impl Builder {
     /// }
     /// ```
     pub fn get_object(mut self, bucket_name: &str, object: &str) -> Executor {
+        let bucket_name = self.bucket_id(bucket_name);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push(bucket_name)
-            .push(object);
+            .extend(object_segments(object));
         self.create_executor()
     }
 
-    async fn shared_upload(mut self, bucket_name: &str, object: &str, file_path: &str) -> Executor {
+    /// get a specific version of an object from the storage
+    ///
+    /// **Experimental**: object versioning isn't generally available in the storage api yet,
+    /// the `version` query parameter may change once it is released.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - a wildcard
+    /// * `version_id` - the version of the object
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_object_version("thefux", "file_name.pdf", "6c1c3e8a-0d1b-4c5e-8f2a-1b9d7e4c3a21")
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn get_object_version(self, bucket_name: &str, object: &str, version_id: &str) -> Executor {
+        let mut executor = self.get_object(bucket_name, object);
+        executor
+            .builder
+            .url
+            .query_pairs_mut()
+            .append_pair("version", version_id);
+        executor
+    }
+
+    /// get an object whose content is json and deserialize it
+    ///
+    /// Unlike `execute_from`, which parses the responses of the storage api,
+    /// the body is the content of the object itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, errors::Error>` - The deserialized content of the object.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Deserialize;
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Settings {
+    ///     theme: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let settings = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_object_json::<Settings>("thefux", "settings.json")
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn get_object_json<T>(
+        self,
+        bucket_name: &str,
+        object: &str,
+    ) -> Result<T, errors::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let response = self
+            .get_object(bucket_name, object)
+            .execute_checked()
+            .await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+
+        serde_json::from_slice(&body).map_err(|error| errors::Error {
+            status_code: status.as_str().to_string(),
+            error: "InvalidJson".to_string(),
+            message: format!("the object {} is not valid json: {}", object, error),
+            context: None,
+        })
+    }
+
+    /// check if the server serves byte ranges of an object
+    ///
+    /// Sends a `HEAD` request and looks for `Accept-Ranges: bytes` in the response, call it
+    /// before requesting parts of an object with a `Range` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, errors::Error>` - Whether byte ranges are supported.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let ranges = Storage::new_with_config(config)
+    ///         .from()
+    ///         .supports_ranges("thefux", "btc.pdf")
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn supports_ranges(
+        self,
+        bucket_name: &str,
+        object: &str,
+    ) -> Result<bool, errors::Error> {
+        let mut executor = self.get_object(bucket_name, object);
+        executor.builder.method = Method::HEAD;
+        let response = executor.execute_checked().await?;
+
+        Ok(response
+            .headers()
+            .get(ACCEPT_RANGES)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| {
+                value
+                    .split(',')
+                    .any(|unit| unit.trim().eq_ignore_ascii_case("bytes"))
+            }))
+    }
+
+    /// uploads the file with the default file options of the `Storage`, if any
+    async fn shared_upload(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+    ) -> Result<Executor, io::Error> {
+        let file = File::open(file_path).await?;
+        let file_options = self.default_file_options.clone();
+        let mut executor = self.shared_upload_reader(bucket_name, object, file);
+        if let Some(file_options) = file_options {
+            executor.builder.apply_file_options(&file_options);
+        }
+        Ok(executor)
+    }
+
+    fn shared_upload_reader<R>(self, bucket_name: &str, object: &str, reader: R) -> Executor
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        let stream = FramedRead::new(reader, BytesCodec::new());
+        self.shared_upload_body(bucket_name, object, Body::wrap_stream(stream))
+    }
+
+    fn shared_upload_body(mut self, bucket_name: &str, object: &str, body: Body) -> Executor {
         let mime = mime_guess::from_path(object)
             .first_or_octet_stream()
             .to_string();
@@ -165,26 +473,30 @@ impl Builder {
             .unwrap()
             .insert("Content-Type", HeaderValue::from_str(&mime).unwrap());
 
+        let bucket_name = self.bucket_id(bucket_name);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push(bucket_name)
-            .push(object);
+            .extend(object_segments(object));
 
-        let file = File::open(file_path).await.unwrap();
-        let stream = FramedRead::new(file, BytesCodec::new());
-        self.body = Some(BodyType::ReqwestBody(Body::wrap_stream(stream)));
+        self.body = Some(BodyType::ReqwestBody(body));
 
         self.create_executor()
     }
 
-    /// update an object
+    /// upload an object from a stream
+    ///
+    /// The length of the stream doesn't need to be known, the body is sent with chunked
+    /// transfer encoding and no `Content-Length` header. This allows to pipe e.g. an
+    /// incoming http upload straight into the storage without buffering it.
     ///
     /// # Arguments
     ///
     /// * `bucket_name` - bucket name
-    /// * `object` - a wildcard
+    /// * `object` - object name
+    /// * `reader` - the source of the object content
     ///
     /// # Returns
     ///
@@ -195,6 +507,135 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    /// use tokio::fs::File;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_object_stream("thefux", "file_name.pdf", File::open("out/test.pdf").await.unwrap())
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn upload_object_stream<R>(mut self, bucket_name: &str, object: &str, reader: R) -> Executor
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        self.method = Method::POST;
+        self.shared_upload_reader(bucket_name, object, reader)
+    }
+
+    /// upload an object streamed from another http url, e.g. to migrate objects from S3
+    ///
+    /// The source is fetched without the headers of the `Storage` and its body is piped
+    /// into the upload, nothing is buffered in memory or on disk. The `Content-Type` of the
+    /// source is kept unless `content_type` is set in the file options.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `source_url` - the url the content is fetched from
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance,
+    ///   or the error fetching the source.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_from_url(
+    ///             "thefux",
+    ///             "btc.pdf",
+    ///             "https://bitcoin.org/bitcoin.pdf",
+    ///             FileOptions {
+    ///                 cache_control: None,
+    ///                 content_type: None,
+    ///                 upsert: Some(true),
+    ///                 content_disposition: None,
+    ///             },
+    ///         )
+    ///         .await
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn upload_from_url(
+        mut self,
+        bucket_name: &str,
+        object: &str,
+        source_url: &str,
+        file_options: FileOptions,
+    ) -> Result<Executor, errors::Error> {
+        // errors built from the reqwest error leave out the query of the source url, which
+        // carries the credentials of presigned urls
+        let request = self.client.get(source_url).build()?;
+        let source = match &self.backend {
+            Some(backend) => backend.send(request).await,
+            None => self.client.execute(request).await,
+        }?
+        .error_for_status()?;
+        let source_content_type = source
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let file_options = self.merge_default_file_options(file_options);
+        let file_options = FileOptions {
+            content_type: file_options.content_type.or(source_content_type),
+            ..file_options
+        };
+
+        self.method = Method::POST;
+        let mut executor = self.shared_upload_body(
+            bucket_name,
+            object,
+            Body::wrap_stream(source.bytes_stream()),
+        );
+        executor.builder.apply_file_options(&file_options);
+        Ok(executor)
+    }
+
+    /// update an object, applying the default file options of the `Storage`
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - a wildcard
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
     ///     model::bucket::NewBucket,
     /// };
     /// use dotenv::dotenv;
@@ -207,6 +648,7 @@ impl Builder {
     ///         .from()
     ///         .update_object_async("thefux", "file_name.pdf", "out/test.pdf")
     ///         .await
+    ///         .unwrap()
     ///         .execute()
     ///         .await
     ///         .unwrap();
@@ -217,12 +659,13 @@ impl Builder {
         bucket_name: &str,
         object: &str,
         file_path: &str,
-    ) -> Executor {
+    ) -> Result<Executor, io::Error> {
         self.method = Method::PUT;
         self.shared_upload(bucket_name, object, file_path).await
     }
 
-    /// upload an object
+    /// upload an object, applying the default file options of the `Storage`,
+    /// see `upload_object_with_options` to pass file options for a single upload
     ///
     /// # Arguments
     ///
@@ -231,14 +674,15 @@ impl Builder {
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
     ///
     /// # Example
     /// ```
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
+    ///     model::object::UploadResult,
     /// };
     /// use dotenv::dotenv;
     ///
@@ -250,7 +694,8 @@ impl Builder {
     ///         .from()
     ///         .upload_object("thefux", "file_name.pdf", "out/test.pdf")
     ///         .await
-    ///         .execute()
+    ///         .unwrap()
+    ///         .execute_from::<UploadResult>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -260,28 +705,62 @@ impl Builder {
         bucket_name: &str,
         object: &str,
         file_path: &str,
-    ) -> Executor {
+    ) -> Result<Executor, io::Error> {
         self.method = Method::POST;
         self.shared_upload(bucket_name, object, file_path).await
     }
 
-    /// download object
+    fn apply_file_options(&mut self, file_options: &FileOptions) {
+        let mut headers = self.headers.lock().unwrap();
+        if let Some(cache_content) = file_options.cache_control {
+            headers.insert(
+                "cache-control",
+                HeaderValue::from_str(&cache_control_value(cache_content)).unwrap(),
+            );
+        }
+
+        match file_options.content_type.as_deref() {
+            Some("") => {
+                headers.remove("content-type");
+            }
+            Some(content_type) => {
+                headers.insert("content-type", HeaderValue::from_str(content_type).unwrap());
+            }
+            None => {}
+        }
+
+        if let Some(upsert) = file_options.upsert {
+            headers.insert(
+                "x-upsert",
+                HeaderValue::from_str(&upsert.to_string()).unwrap(),
+            );
+        }
+        drop(headers);
+
+        if let Some(content_disposition) = &file_options.content_disposition {
+            self.content_disposition(content_disposition);
+        }
+    }
+
+    /// upload an object using the default file options of the `Storage`, same as `upload_object`
     ///
     /// # Arguments
     ///
-    /// * `bucket_id` - bucket id
-    /// * `body` - request body
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
     ///
     /// # Example
     /// ```
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
+    ///     model::options::FileOptions,
     /// };
     /// use dotenv::dotenv;
     ///
@@ -290,59 +769,1444 @@ impl Builder {
     ///     dotenv().ok();
     ///     let config = SupabaseConfig::default();
     ///     let response = Storage::new_with_config(config)
+    ///         .with_default_file_options(FileOptions {
+    ///             cache_control: Some(3600),
+    ///             content_type: None,
+    ///             upsert: Some(true),
+    ///             content_disposition: None,
+    ///         })
     ///         .from()
-    ///         .download_object("thefux")
+    ///         .upload_object_default("thefux", "file_name.pdf", "out/test.pdf")
+    ///         .await
+    ///         .unwrap()
     ///         .execute()
     ///         .await
     ///         .unwrap();
     /// }
     /// ```
-    pub fn download_object(mut self, bucket_id: &str) -> Executor {
-        self.headers
-            .lock()
-            .unwrap()
-            .insert("Content-Type", HeaderValue::from_static("application/json"));
-        self.method = Method::POST;
-        self.url
-            .path_segments_mut()
-            .unwrap()
-            .push("object")
-            .push(bucket_id);
-        self.create_executor()
+    pub async fn upload_object_default(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+    ) -> Result<Executor, io::Error> {
+        self.upload_object(bucket_name, object, file_path).await
     }
-}
-
-#[cfg(test)]
-mod test {
-    use reqwest::{header::HeaderMap, Client};
-    use std::sync::{Arc, Mutex};
-    use url::{Host, Origin};
-
-    use crate::build::builder::Builder;
 
-    #[test]
-    fn test_download_object() {
-        let executor = Builder::new(
-            url::Url::parse("http://localhost").unwrap(),
-            Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
-        )
-        .download_object("test_bucket");
-
-        assert_eq!(
+    /// upload an object with file options, unset options fall back to the default file options
+    /// of the `Storage`
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_object_with_options("thefux", "file_name.pdf", "out/test.pdf", FileOptions {
+    ///             cache_control: Some(60),
+    ///             content_type: Some("application/pdf".to_string()),
+    ///             upsert: Some(false),
+    ///             content_disposition: None,
+    ///         })
+    ///         .await
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn upload_object_with_options(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<Executor, io::Error> {
+        let file_options = self.merge_default_file_options(file_options);
+        let mut executor = self.upload_object(bucket_name, object, file_path).await?;
+        executor.builder.apply_file_options(&file_options);
+        Ok(executor)
+    }
+
+    /// update an object with file options, unset options fall back to the default file options
+    /// of the `Storage`
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .update_object_with_options("thefux", "file_name.pdf", "out/test.pdf", FileOptions {
+    ///             cache_control: Some(60),
+    ///             content_type: None,
+    ///             upsert: None,
+    ///             content_disposition: None,
+    ///         })
+    ///         .await
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn update_object_with_options(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<Executor, io::Error> {
+        let file_options = self.merge_default_file_options(file_options);
+        let mut executor = self
+            .update_object_async(bucket_name, object, file_path)
+            .await?;
+        executor.builder.apply_file_options(&file_options);
+        Ok(executor)
+    }
+
+    /// fills the unset options with the default file options of the `Storage`
+    fn merge_default_file_options(&self, file_options: FileOptions) -> FileOptions {
+        match &self.default_file_options {
+            Some(defaults) => FileOptions {
+                cache_control: file_options.cache_control.or(defaults.cache_control),
+                content_type: file_options
+                    .content_type
+                    .or_else(|| defaults.content_type.clone()),
+                upsert: file_options.upsert.or(defaults.upsert),
+                content_disposition: file_options
+                    .content_disposition
+                    .or_else(|| defaults.content_disposition.clone()),
+            },
+            None => file_options,
+        }
+    }
+
+    /// upload a value serialized as json, with the `Content-Type: application/json` header
+    ///
+    /// Unset options fall back to the default file options of the `Storage`,
+    /// a `content_type` option is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `value` - the value stored as the content of the object
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance,
+    ///   or the error serializing the value.
+    ///
+    /// # Example
+    /// ```
+    /// use serde::Serialize;
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Settings {
+    ///     theme: String,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let settings = Settings { theme: "dark".to_string() };
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .put_object_json("thefux", "settings.json", &settings, FileOptions {
+    ///             cache_control: Some(60),
+    ///             content_type: None,
+    ///             upsert: Some(true),
+    ///             content_disposition: None,
+    ///         })
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn put_object_json<T>(
+        mut self,
+        bucket_name: &str,
+        object: &str,
+        value: &T,
+        file_options: FileOptions,
+    ) -> Result<Executor, errors::Error>
+    where
+        T: Serialize,
+    {
+        let body = serde_json::to_vec(value).map_err(|error| errors::Error {
+            status_code: String::new(),
+            error: "InvalidJson".to_string(),
+            message: format!("the value can't be serialized as json: {}", error),
+            context: None,
+        })?;
+
+        let file_options = self.merge_default_file_options(file_options);
+        self.method = Method::POST;
+        let mut executor = self.shared_upload_body(bucket_name, object, Body::from(body));
+        executor.builder.apply_file_options(&FileOptions {
+            content_type: Some("application/json".to_string()),
+            ..file_options
+        });
+        Ok(executor)
+    }
+
+    /// upload an object, failing fast if the file exceeds the `file_size_limit` of the bucket
+    ///
+    /// The check costs an additional request to fetch the bucket details first, use
+    /// `upload_object` to upload without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or a `FileTooLarge` error.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_object_checked("thefux", "file_name.pdf", "out/test.pdf")
+    ///         .await
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn upload_object_checked(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+    ) -> Result<Executor, errors::Error> {
+        let bucket = self
+            .fork()
+            .get_bucket_details(bucket_name)
+            .execute_from::<BucketDetails>()
+            .await?;
+
+        if let Some(limit) = bucket.file_size_limit {
+            let size = tokio::fs::metadata(file_path).await?.len();
+            if size > limit {
+                return Err(errors::Error {
+                    status_code: "413".to_string(),
+                    error: "FileTooLarge".to_string(),
+                    message: format!(
+                        "the file has {} bytes, the bucket {} allows at most {} bytes",
+                        size, bucket_name, limit
+                    ),
+                    context: None,
+                });
+            }
+        }
+
+        Ok(self.upload_object(bucket_name, object, file_path).await?)
+    }
+
+    /// upload an object, falls back to an update if the object already exists
+    ///
+    /// The object is uploaded first (POST), if the server answers with a `Duplicate` error
+    /// the upload is retried as an update (PUT).
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, errors::Error>` - The deserialized response of the upload or the update.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_or_update_object::<serde_json::Value>("thefux", "file_name.pdf", "out/test.pdf", FileOptions {
+    ///             cache_control: None,
+    ///             content_type: None,
+    ///             upsert: Some(false),
+    ///             content_disposition: None,
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn upload_or_update_object<T>(
+        self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<T, errors::Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let update = self.fork();
+
+        let mut executor = self.upload_object(bucket_name, object, file_path).await?;
+        executor.builder.apply_file_options(&file_options);
+
+        match executor.execute_from::<T>().await {
+            Err(error) if error.kind() == ErrorKind::Duplicate => {
+                let mut executor = update
+                    .update_object_async(bucket_name, object, file_path)
+                    .await?;
+                executor.builder.apply_file_options(&file_options);
+                executor.execute_from::<T>().await
+            }
+            result => result,
+        }
+    }
+
+    /// upload an object under a key derived from its content, the hex encoded sha256 digest
+    /// of the file followed by the extension of `file_path`
+    ///
+    /// The file is hashed without reading it into memory. A `HEAD` request checks whether the
+    /// key exists first, if it does the upload is skipped since the existing object has the
+    /// same content. A `Duplicate` error of an object uploaded in the meantime is ignored too.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, errors::Error>` - The key of the object.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let key = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_content_addressed("thefux", "out/test.pdf", FileOptions {
+    ///             cache_control: Some(31536000),
+    ///             content_type: None,
+    ///             upsert: Some(false),
+    ///             content_disposition: None,
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn upload_content_addressed(
+        self,
+        bucket_name: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<String, errors::Error> {
+        let mut file = File::open(file_path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        let mut key = format!("{:x}", hasher.finalize());
+        if let Some(extension) = Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            key = format!("{}.{}", key, extension);
+        }
+
+        let mut exists = self.fork().get_object(bucket_name, &key);
+        exists.builder.method = Method::HEAD;
+        // any error means the object isn't known to exist, the upload reports real failures
+        if exists.execute_checked().await.is_ok() {
+            return Ok(key);
+        }
+
+        let mut executor = self.upload_object(bucket_name, &key, file_path).await?;
+        executor.builder.apply_file_options(&file_options);
+        match executor.execute_from::<UploadResult>().await {
+            Ok(_) => Ok(key),
+            Err(error) if error.kind() == ErrorKind::Duplicate => Ok(key),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// download an object, optionally transforming it before it's served
+    ///
+    /// Without a transformation the object is fetched like `get_object`, with a transformation
+    /// like `get_object_with_transform`.
+    ///
+    /// Previously `download_object(bucket_id)` sent a `POST /object/{bucket_id}` without a body,
+    /// which doesn't match an endpoint of the storage api. Replace such calls with
+    /// `download_object(bucket_id, object, None)` or `get_object(bucket_id, object)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `transform` - transformation applied to the object, e.g. to resize an image
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::Transform,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .download_object("thefux", "test.png", Some(Transform::webp_optimized()))
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn download_object(
+        self,
+        bucket_id: &str,
+        object: &str,
+        transform: Option<Transform>,
+    ) -> Executor {
+        match transform {
+            Some(transform) => self.get_object_with_transform(bucket_id, object, transform),
+            None => self.get_object(bucket_id, object),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::{header::HeaderMap, Client, Method};
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncWriteExt;
+    use url::{Host, Origin};
+
+    use super::LIST_PAGE_SIZE;
+    use crate::{
+        build::builder::Builder,
+        model::{
+            errors::ErrorKind,
+            object::ObjectMetadata,
+            options::{FileOptions, ListObjectsOptions, Transform},
+        },
+        testing::MockBackend,
+        Storage,
+    };
+
+    #[test]
+    fn test_download_object() {
+        let builder = || {
+            Builder::new(
+                url::Url::parse("http://localhost").unwrap(),
+                Arc::new(Mutex::new(HeaderMap::new())),
+                Client::new(),
+            )
+        };
+
+        let executor = builder().download_object("test_bucket", "docs/btc.pdf", None);
+        assert_eq!(executor.builder.method, Method::GET);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/object/test_bucket/docs/btc.pdf"
+        );
+        assert!(executor.builder.body.is_none());
+
+        let executor = builder().download_object(
+            "test_bucket",
+            "test.png",
+            Some(Transform {
+                width: Some(100),
+                ..Transform::webp_optimized()
+            }),
+        );
+        assert_eq!(executor.builder.method, Method::GET);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/render/image/authenticated/test_bucket/test.png?format=webp&quality=75&width=100"
+        );
+        assert_eq!(
+            executor.builder.url.origin(),
+            Origin::Tuple("http".into(), Host::Domain("localhost".into()), 80)
+        );
+    }
+
+    #[test]
+    fn test_get_object_version() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .get_object_version("thefux", "btc.pdf", "v1");
+
+        assert_eq!(executor.builder.method, Method::GET);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/object/thefux/btc.pdf?version=v1"
+        );
+    }
+
+    #[test]
+    fn test_object_key_slashes_are_trimmed() {
+        let builder = || {
+            Builder::new(
+                url::Url::parse("http://localhost").unwrap(),
+                Arc::new(Mutex::new(HeaderMap::new())),
+                Client::new(),
+            )
+        };
+
+        let executor = builder().get_object("bucket", "/folder/file.pdf");
+        assert_eq!(
+            executor.builder.url.path(),
+            "/object/bucket/folder/file.pdf"
+        );
+
+        let executor = builder().delete_object("bucket", "folder//file.pdf/");
+        assert_eq!(
+            executor.builder.url.path(),
+            "/object/bucket/folder/file.pdf"
+        );
+    }
+
+    const NESTED_KEY: &str = "a/b/c/d/e/f/file.pdf";
+
+    #[tokio::test]
+    async fn test_nested_upload_keeps_full_path() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/a/b/c/d/e/f/file.pdf"}"#);
+        backend.push_response(200, &[], r#"{"Key":"thefux/a/b/c/d/e/f/file.pdf"}"#);
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        storage
+            .from()
+            .upload_object("thefux", NESTED_KEY, "Cargo.toml")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+        storage
+            .from()
+            .update_object_async("thefux", &format!("/{}/", NESTED_KEY), "Cargo.toml")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = backend.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[1].method, Method::PUT);
+        for request in requests {
+            assert_eq!(request.url.path(), "/object/thefux/a/b/c/d/e/f/file.pdf");
+        }
+    }
+
+    #[test]
+    fn test_nested_keys_for_every_endpoint() {
+        let storage = Storage::new("http://localhost");
+
+        assert_eq!(
+            storage.from().get_object("thefux", NESTED_KEY).url().path(),
+            "/object/thefux/a/b/c/d/e/f/file.pdf"
+        );
+        assert_eq!(
+            storage
+                .from()
+                .get_public_object("thefux", NESTED_KEY)
+                .url()
+                .path(),
+            "/object/public/thefux/a/b/c/d/e/f/file.pdf"
+        );
+        assert_eq!(
+            storage
+                .from()
+                .create_signed_upload_url("thefux", NESTED_KEY)
+                .url()
+                .path(),
+            "/object/upload/sign/thefux/a/b/c/d/e/f/file.pdf"
+        );
+        assert_eq!(
+            storage
+                .from()
+                .delete_object("thefux", NESTED_KEY)
+                .url()
+                .path(),
+            "/object/thefux/a/b/c/d/e/f/file.pdf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_upload_listed_under_parent_prefix() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/a/b/c/d/e/f/file.pdf"}"#);
+        backend.push_response(200, &[], r#"[{"name":"file.pdf","id":"file"}]"#);
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        storage
+            .from()
+            .upload_object("thefux", NESTED_KEY, "Cargo.toml")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+        let objects = storage
+            .from()
+            .list_objects_from("thefux", ListObjectsOptions::new("a/b/c/d/e/f"))
+            .execute_from::<Vec<ObjectMetadata>>()
+            .await
+            .unwrap();
+
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].name, "file.pdf");
+
+        let requests = backend.requests();
+        let body: serde_json::Value =
+            serde_json::from_slice(requests[1].body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["prefix"], "a/b/c/d/e/f");
+    }
+
+    #[tokio::test]
+    async fn test_upload_or_update_object_on_duplicate() {
+        let mut server = mockito::Server::new_async().await;
+        let upload = server
+            .mock("POST", "/object/thefux/btc.pdf")
+            .with_status(409)
+            .with_body(r#"{"statusCode":"409","error":"Duplicate","message":"The resource already exists"}"#)
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/object/thefux/btc.pdf")
+            .match_header("x-upsert", "false")
+            .with_status(200)
+            .with_body(r#"{"Key":"thefux/btc.pdf"}"#)
+            .create_async()
+            .await;
+
+        let response = Builder::new(
+            url::Url::parse(&server.url()).unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .upload_or_update_object::<serde_json::Value>(
+            "thefux",
+            "btc.pdf",
+            "out/test.pdf",
+            FileOptions {
+                cache_control: None,
+                content_type: None,
+                upsert: Some(false),
+                content_disposition: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        upload.assert_async().await;
+        update.assert_async().await;
+        assert_eq!(response["Key"], "thefux/btc.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_missing_file() {
+        let backend = Arc::new(MockBackend::new());
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let error = storage
+            .from()
+            .upload_object("thefux", "btc.pdf", "out/does-not-exist.pdf")
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+        let error = storage
+            .from()
+            .upload_or_update_object::<serde_json::Value>(
+                "thefux",
+                "btc.pdf",
+                "out/does-not-exist.pdf",
+                FileOptions {
+                    cache_control: None,
+                    content_type: None,
+                    upsert: None,
+                    content_disposition: None,
+                },
+            )
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.error, "IoError");
+        assert!(backend.requests().is_empty());
+    }
+
+    fn storage_with_defaults() -> Storage {
+        Storage::new("http://localhost").with_default_file_options(FileOptions {
+            cache_control: Some(3600),
+            content_type: None,
+            upsert: Some(true),
+            content_disposition: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_default() {
+        let executor = storage_with_defaults()
+            .from()
+            .upload_object_default("thefux", "btc.pdf", "out/test.pdf")
+            .await
+            .unwrap();
+
+        let headers = executor.builder.headers.lock().unwrap();
+        assert_eq!(headers.get("cache-control").unwrap(), "max-age=3600");
+        assert_eq!(headers.get("x-upsert").unwrap(), "true");
+        assert_eq!(headers.get("content-type").unwrap(), "application/pdf");
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_applies_file_options() {
+        let executor = storage_with_defaults()
+            .from()
+            .upload_object("thefux", "btc.pdf", "out/test.pdf")
+            .await
+            .unwrap();
+        assert_eq!(
             executor
                 .builder
                 .headers
                 .lock()
                 .unwrap()
-                .get("Content-Type")
+                .get("cache-control")
                 .unwrap(),
+            "max-age=3600"
+        );
+
+        let executor = storage_with_defaults()
+            .from()
+            .update_object_with_options(
+                "thefux",
+                "btc.pdf",
+                "out/test.pdf",
+                FileOptions {
+                    cache_control: Some(60),
+                    content_type: None,
+                    upsert: None,
+                    content_disposition: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(executor.builder.method, Method::PUT);
+        let headers = executor.builder.headers.lock().unwrap();
+        assert_eq!(headers.get("cache-control").unwrap(), "max-age=60");
+        assert_eq!(headers.get("x-upsert").unwrap(), "true");
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_with_options_overrides_defaults() {
+        let executor = storage_with_defaults()
+            .from()
+            .upload_object_with_options(
+                "thefux",
+                "btc.pdf",
+                "out/test.pdf",
+                FileOptions {
+                    cache_control: None,
+                    content_type: Some("application/octet-stream".to_string()),
+                    upsert: Some(false),
+                    content_disposition: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let headers = executor.builder.headers.lock().unwrap();
+        assert_eq!(headers.get("cache-control").unwrap(), "max-age=3600");
+        assert_eq!(headers.get("x-upsert").unwrap(), "false");
+        assert_eq!(
+            headers.get("content-type").unwrap(),
+            "application/octet-stream"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_content_disposition() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/1f3a.pdf"}"#);
+
+        Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .upload_object_with_options(
+                "thefux",
+                "1f3a.pdf",
+                "out/test.pdf",
+                FileOptions {
+                    cache_control: None,
+                    content_type: None,
+                    upsert: None,
+                    content_disposition: Some(r#"attachment; filename="report.pdf""#.to_string()),
+                },
+            )
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = backend.requests();
+        assert_eq!(
+            requests[0].headers.get("content-disposition").unwrap(),
+            r#"attachment; filename="report.pdf""#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_non_ascii_content_disposition() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/1f3a.pdf"}"#);
+        let file_options = |content_disposition: &str| FileOptions {
+            cache_control: None,
+            content_type: None,
+            upsert: None,
+            content_disposition: Some(content_disposition.to_string()),
+        };
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        storage
+            .from()
+            .upload_object_with_options(
+                "thefux",
+                "1f3a.pdf",
+                "out/test.pdf",
+                file_options(r#"attachment; filename="résumé.pdf""#),
+            )
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = backend.requests();
+        assert_eq!(
+            requests[0].headers.get("content-disposition").unwrap(),
+            r#"attachment; filename="r_sum_.pdf"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"#
+        );
+
+        let error = storage
+            .from()
+            .upload_object_with_options(
+                "thefux",
+                "1f3a.pdf",
+                "out/test.pdf",
+                file_options(r#"attachment; name="résumé""#),
+            )
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(error.error, "InvalidContentDisposition");
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_without_content_type() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/1f3a.pdf"}"#);
+
+        Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .upload_object_with_options(
+                "thefux",
+                "1f3a.pdf",
+                "out/test.pdf",
+                FileOptions {
+                    cache_control: None,
+                    content_type: Some(String::new()),
+                    upsert: None,
+                    content_disposition: None,
+                },
+            )
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        assert!(backend.requests()[0].headers.get("content-type").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reuse_cloned_file_options() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/a.pdf"}"#);
+        backend.push_response(200, &[], r#"{"Key":"thefux/b.pdf"}"#);
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let file_options = FileOptions {
+            cache_control: Some(60),
+            content_type: Some("application/pdf".to_string()),
+            upsert: Some(true),
+            content_disposition: None,
+        };
+        let copy = file_options.clone();
+        assert_eq!(copy, file_options);
+
+        for (object, options) in [("a.pdf", file_options), ("b.pdf", copy)] {
+            storage
+                .from()
+                .upload_object_with_options("thefux", object, "Cargo.toml", options)
+                .await
+                .unwrap()
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        for request in backend.requests() {
+            assert_eq!(request.headers.get("cache-control").unwrap(), "max-age=60");
+            assert_eq!(request.headers.get("x-upsert").unwrap(), "true");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_checked_file_too_large() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[],
+            r#"{"name":"thefux","id":"thefux","public":false,"file_size_limit":1}"#,
+        );
+
+        let result = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .upload_object_checked("thefux", "Cargo.toml", "Cargo.toml")
+            .await;
+
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::FileTooLarge);
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url.path(), "/bucket/thefux");
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_checked_within_limit() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[],
+            r#"{"name":"thefux","id":"thefux","public":false,"file_size_limit":1048576}"#,
+        );
+        backend.push_response(200, &[], r#"{"Key":"thefux/Cargo.toml"}"#);
+
+        Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .upload_object_checked("thefux", "Cargo.toml", "Cargo.toml")
+            .await
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, Method::POST);
+        assert_eq!(requests[1].url.path(), "/object/thefux/Cargo.toml");
+    }
+
+    #[tokio::test]
+    async fn test_update_object_if_match_precondition_failed() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(412, &[], "");
+
+        let error = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .if_match("\"abc\"")
+            .update_object_async("thefux", "Cargo.toml", "Cargo.toml")
+            .await
+            .unwrap()
+            .execute_from::<serde_json::Value>()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::PreconditionFailed);
+        assert_eq!(
+            backend.requests()[0].headers.get("If-Match").unwrap(),
+            "\"abc\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_object_invalid_if_match() {
+        let backend = Arc::new(MockBackend::new());
+
+        let error = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .if_match("\"abc\"\n")
+            .update_object_async("thefux", "Cargo.toml", "Cargo.toml")
+            .await
+            .unwrap()
+            .execute_from::<serde_json::Value>()
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.error, "InvalidHeader");
+        assert!(backend.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_object_json() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Settings {
+            theme: String,
+            columns: u32,
+        }
+
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"theme":"dark","columns":3}"#,
+        );
+        backend.push_response(200, &[], "not json");
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let settings = storage
+            .from()
+            .get_object_json::<Settings>("thefux", "config/settings.json")
+            .await
+            .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                theme: "dark".to_string(),
+                columns: 3,
+            }
+        );
+        assert_eq!(
+            backend.requests()[0].url.path(),
+            "/object/thefux/config/settings.json"
+        );
+
+        let error = storage
+            .from()
+            .get_object_json::<Settings>("thefux", "settings.json")
+            .await
+            .unwrap_err();
+        assert_eq!(error.error, "InvalidJson");
+    }
+
+    #[tokio::test]
+    async fn test_supports_ranges() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[("accept-ranges", "bytes")], "");
+        backend.push_response(200, &[], "");
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        assert!(storage
+            .from()
+            .supports_ranges("thefux", "btc.pdf")
+            .await
+            .unwrap());
+        assert!(!storage
+            .from()
+            .supports_ranges("thefux", "btc.pdf")
+            .await
+            .unwrap());
+
+        let requests = backend.requests();
+        assert_eq!(requests[0].method, Method::HEAD);
+        assert_eq!(requests[0].url.path(), "/object/thefux/btc.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_put_object_json() {
+        #[derive(serde::Serialize)]
+        struct Settings {
+            theme: String,
+            columns: u32,
+        }
+
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"Key":"thefux/settings.json"}"#);
+
+        Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .put_object_json(
+                "thefux",
+                "config/settings",
+                &Settings {
+                    theme: "dark".to_string(),
+                    columns: 3,
+                },
+                FileOptions {
+                    cache_control: Some(60),
+                    content_type: Some("text/plain".to_string()),
+                    upsert: Some(true),
+                    content_disposition: None,
+                },
+            )
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        let request = &backend.requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url.path(), "/object/thefux/config/settings");
+        assert_eq!(
+            request.body.as_deref(),
+            Some(br#"{"theme":"dark","columns":3}"#.as_slice())
+        );
+        assert_eq!(
+            request.headers.get("content-type").unwrap(),
             "application/json"
         );
-        assert_eq!(executor.builder.url.path(), "/object/test_bucket");
+        assert_eq!(request.headers.get("cache-control").unwrap(), "max-age=60");
+        assert_eq!(request.headers.get("x-upsert").unwrap(), "true");
+    }
+
+    #[tokio::test]
+    async fn test_upload_content_addressed() {
+        let digest = "b6db625728082cdf27bfc62ee3542241dca8265465116c451b1c10b2965079cf";
+        let file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        let path = file.path();
+        tokio::fs::write(path, "hello supabase").await.unwrap();
+
+        let backend = Arc::new(MockBackend::new());
+        // uploaded
+        backend.push_response(404, &[], "");
+        backend.push_response(200, &[], format!(r#"{{"Key":"thefux/{}.txt"}}"#, digest));
+        // already exists, skipped
+        backend.push_response(200, &[], "");
+        // uploaded in the meantime
+        backend.push_response(404, &[], "");
+        backend.push_response(
+            409,
+            &[],
+            r#"{"statusCode":"409","error":"Duplicate","message":"The resource already exists"}"#,
+        );
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+        let file_options = FileOptions {
+            cache_control: Some(31536000),
+            content_type: None,
+            upsert: Some(false),
+            content_disposition: None,
+        };
+
+        for _ in 0..3 {
+            let key = storage
+                .from()
+                .upload_content_addressed("thefux", path.to_str().unwrap(), file_options.clone())
+                .await
+                .unwrap();
+            assert_eq!(key, format!("{}.txt", digest));
+        }
+
+        let requests = backend.requests();
+        let methods: Vec<_> = requests
+            .iter()
+            .map(|request| request.method.clone())
+            .collect();
         assert_eq!(
-            executor.builder.url.origin(),
-            Origin::Tuple("http".into(), Host::Domain("localhost".into()), 80)
+            methods,
+            [
+                Method::HEAD,
+                Method::POST,
+                Method::HEAD,
+                Method::HEAD,
+                Method::POST
+            ]
+        );
+        for request in &requests {
+            assert_eq!(request.url.path(), format!("/object/thefux/{}.txt", digest));
+        }
+        assert_eq!(
+            requests[1].headers.get("cache-control").unwrap(),
+            "max-age=31536000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_header_matches_serialized_options() {
+        let file_options = FileOptions {
+            cache_control: Some(3600),
+            content_type: None,
+            upsert: None,
+            content_disposition: None,
+        };
+        let executor = Storage::new("http://localhost")
+            .from()
+            .upload_object_with_options("thefux", "btc.pdf", "out/test.pdf", file_options.clone())
+            .await
+            .unwrap();
+
+        let serialized = serde_json::to_value(&file_options).unwrap();
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("cache-control")
+                .unwrap(),
+            serialized["cache-control"].as_str().unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_upload_from_url() {
+        let mut source = mockito::Server::new_async().await;
+        let download = source
+            .mock("GET", "/bucket/btc.pdf")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_header("content-type", "application/pdf")
+            .with_body("the bitcoin whitepaper")
+            .create_async()
+            .await;
+
+        let mut storage = mockito::Server::new_async().await;
+        let upload = storage
+            .mock("POST", "/object/thefux/migrated/btc")
+            .match_header("authorization", "Bearer key")
+            .match_header("content-type", "application/pdf")
+            .match_header("x-upsert", "true")
+            .match_body("the bitcoin whitepaper")
+            .with_body(r#"{"Key":"thefux/migrated/btc"}"#)
+            .create_async()
+            .await;
+
+        let response = Storage::new_with_config(crate::config::SupabaseConfig {
+            supabase_url_storage: storage.url(),
+            supabase_api_key: Some("key".to_string()),
+        })
+        .from()
+        .upload_from_url(
+            "thefux",
+            "migrated/btc",
+            &format!("{}/bucket/btc.pdf", source.url()),
+            FileOptions {
+                cache_control: None,
+                content_type: None,
+                upsert: Some(true),
+                content_disposition: None,
+            },
+        )
+        .await
+        .unwrap()
+        .execute()
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), 200);
+        download.assert_async().await;
+        upload.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_from_url_source_error() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(403, &[], "expired");
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let error = storage
+            .from()
+            .upload_from_url(
+                "thefux",
+                "btc.pdf",
+                "http://source.localhost/btc.pdf?X-Amz-Signature=secret",
+                FileOptions {
+                    cache_control: None,
+                    content_type: None,
+                    upsert: None,
+                    content_disposition: None,
+                },
+            )
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(error.status_code, "403");
+        assert!(!error.message.contains("secret"));
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url.host_str(), Some("source.localhost"));
+        assert!(requests[0].headers.get("authorization").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upload_object_stream_unknown_length() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/object/thefux/stream.txt")
+            .match_header("transfer-encoding", "chunked")
+            .match_header("content-length", mockito::Matcher::Missing)
+            .match_body("hello from a pipe")
+            .with_body(r#"{"Key":"thefux/stream.txt"}"#)
+            .create_async()
+            .await;
+
+        let (mut writer, reader) = tokio::io::duplex(4);
+        tokio::spawn(async move {
+            writer.write_all(b"hello from a pipe").await.unwrap();
+        });
+
+        let response = Storage::new(server.url())
+            .from()
+            .upload_object_stream("thefux", "stream.txt", reader)
+            .execute()
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_delete_prefix() {
+        let backend = Arc::new(MockBackend::new());
+        let first_page = (0..LIST_PAGE_SIZE)
+            .map(|i| format!(r#"{{"name":"{}.pdf","id":"{}"}}"#, i, i))
+            .collect::<Vec<_>>()
+            .join(",");
+        backend.push_response(200, &[], format!("[{}]", first_page));
+        backend.push_response(200, &[], r#"[{"name":"last.pdf","id":"last"}]"#);
+        let deleted = (0..=LIST_PAGE_SIZE)
+            .map(|_| "{}")
+            .collect::<Vec<_>>()
+            .join(",");
+        backend.push_response(200, &[], format!("[{}]", deleted));
+
+        let result = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .delete_prefix("thefux", "/folder/")
+            .await
+            .unwrap();
+
+        assert_eq!(result.deleted, LIST_PAGE_SIZE as usize + 1);
+        assert!(result.failures.is_empty());
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 3);
+        let second_page: serde_json::Value =
+            serde_json::from_slice(requests[1].body.as_ref().unwrap()).unwrap();
+        assert_eq!(second_page["prefix"], "folder");
+        assert_eq!(second_page["offset"], LIST_PAGE_SIZE);
+
+        assert_eq!(requests[2].method, Method::DELETE);
+        let delete: serde_json::Value =
+            serde_json::from_slice(requests[2].body.as_ref().unwrap()).unwrap();
+        assert_eq!(delete["prefixes"][0], "folder/0.pdf");
+        assert_eq!(delete["prefixes"][100], "folder/last.pdf");
+    }
 }
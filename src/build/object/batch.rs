@@ -0,0 +1,374 @@
+use std::future::Future;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+
+use crate::{
+    build::builder::Builder,
+    model::{
+        errors,
+        object::{Response, UploadResult},
+        options::Concurrency,
+    },
+};
+
+async fn run_batch<I, F, Fut, T>(items: Vec<I>, concurrency: &Concurrency, mut f: F) -> Vec<T>
+where
+    F: FnMut(I) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let requests = stream::iter(items).map(|item| {
+        let rate_limit = concurrency.rate_limit.clone();
+        let request = f(item);
+        async move {
+            if let Some(rate_limit) = rate_limit {
+                rate_limit.acquire().await;
+            }
+            request.await
+        }
+    });
+    // a limit of 0 would never poll a request, run them one at a time instead
+    let limit = concurrency.limit.max(1);
+    if concurrency.ordered {
+        requests.buffered(limit).collect().await
+    } else {
+        requests.buffer_unordered(limit).collect().await
+    }
+}
+
+impl Builder {
+    /// upload multiple objects concurrently
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `objects` - pairs of object name and file path
+    /// * `concurrency` - the number of parallel uploads, the ordering of the results and an
+    ///   optional rate limit
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Result<UploadResult, errors::Error>>` - The result of each upload, in the order
+    ///   of `objects` if `concurrency.ordered` is set.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::Concurrency,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let results = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_objects(
+    ///             "thefux",
+    ///             vec![("a.pdf".to_string(), "out/test.pdf".to_string())],
+    ///             Concurrency::default(),
+    ///         )
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn upload_objects(
+        self,
+        bucket_name: &str,
+        objects: Vec<(String, String)>,
+        concurrency: Concurrency,
+    ) -> Vec<Result<UploadResult, errors::Error>> {
+        run_batch(objects, &concurrency, |(object, file_path)| {
+            let builder = self.fork();
+            async move {
+                builder
+                    .upload_object(bucket_name, &object, &file_path)
+                    .await?
+                    .execute_from::<UploadResult>()
+                    .await
+            }
+        })
+        .await
+    }
+
+    /// download multiple objects concurrently
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `objects` - object names
+    /// * `concurrency` - the number of parallel downloads, the ordering of the results and an
+    ///   optional rate limit
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Result<Bytes, errors::Error>>` - The content of each object, in the order
+    ///   of `objects` if `concurrency.ordered` is set.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::Concurrency,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let results = Storage::new_with_config(config)
+    ///         .from()
+    ///         .download_objects("thefux", vec!["a.pdf".to_string()], Concurrency::default())
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn download_objects(
+        self,
+        bucket_name: &str,
+        objects: Vec<String>,
+        concurrency: Concurrency,
+    ) -> Vec<Result<Bytes, errors::Error>> {
+        run_batch(objects, &concurrency, |object| {
+            let builder = self.fork();
+            async move {
+                let response = builder
+                    .get_object(bucket_name, &object)
+                    .execute_checked()
+                    .await?;
+                Ok(response.bytes().await?)
+            }
+        })
+        .await
+    }
+
+    /// move multiple objects concurrently
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `objects` - pairs of object source and destination
+    /// * `concurrency` - the number of parallel moves, the ordering of the results and an
+    ///   optional rate limit
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Result<Response, errors::Error>>` - The result of each move, in the order
+    ///   of `objects` if `concurrency.ordered` is set.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::Concurrency,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let results = Storage::new_with_config(config)
+    ///         .from()
+    ///         .move_objects(
+    ///             "thefux",
+    ///             vec![("from.pdf".to_string(), "to.pdf".to_string())],
+    ///             Concurrency::default(),
+    ///         )
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn move_objects(
+        self,
+        bucket_id: &str,
+        objects: Vec<(String, String)>,
+        concurrency: Concurrency,
+    ) -> Vec<Result<Response, errors::Error>> {
+        run_batch(objects, &concurrency, |(from, to)| {
+            let builder = self.fork();
+            async move {
+                builder
+                    .move_object(bucket_id, &from, &to)
+                    .execute_from::<Response>()
+                    .await
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use reqwest::{Error, Request};
+    use tokio::{sync::oneshot, time::Instant};
+
+    use crate::{
+        build::backend::HttpBackend, model::options::Concurrency, testing::MockBackend,
+        util::rate_limit::RateLimiter, Storage,
+    };
+
+    /// answers with the requested path, each request waits for the next one to complete so
+    /// the requests complete in reverse order
+    struct ReverseBackend {
+        gates: Mutex<HashMap<u64, oneshot::Receiver<()>>>,
+        releases: Mutex<HashMap<u64, oneshot::Sender<()>>>,
+    }
+
+    impl ReverseBackend {
+        fn new(count: u64) -> Self {
+            let mut gates = HashMap::new();
+            let mut releases = HashMap::new();
+            for index in 0..count.saturating_sub(1) {
+                let (release, gate) = oneshot::channel();
+                gates.insert(index, gate);
+                releases.insert(index, release);
+            }
+            Self {
+                gates: Mutex::new(gates),
+                releases: Mutex::new(releases),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl HttpBackend for ReverseBackend {
+        async fn send(&self, request: Request) -> Result<reqwest::Response, Error> {
+            let path = request.url().path().to_string();
+            let index: u64 = path
+                .trim_end_matches(".txt")
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .parse()
+                .unwrap();
+            let gate = self.gates.lock().unwrap().remove(&index);
+            if let Some(gate) = gate {
+                gate.await.unwrap();
+            }
+            if let Some(previous) = index.checked_sub(1) {
+                if let Some(release) = self.releases.lock().unwrap().remove(&previous) {
+                    release.send(()).unwrap();
+                }
+            }
+            Ok(http::Response::new(path).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_objects_ordered() {
+        let objects: Vec<String> = (0..5).map(|i| format!("{}.txt", i)).collect();
+
+        let results = Storage::new("http://localhost")
+            .with_backend(Arc::new(ReverseBackend::new(5)))
+            .from()
+            .download_objects(
+                "thefux",
+                objects.clone(),
+                Concurrency {
+                    limit: 5,
+                    ordered: true,
+                    rate_limit: None,
+                },
+            )
+            .await;
+
+        let paths: Vec<String> = results
+            .into_iter()
+            .map(|result| String::from_utf8(result.unwrap().to_vec()).unwrap())
+            .collect();
+        let expected: Vec<String> = objects
+            .iter()
+            .map(|object| format!("/object/thefux/{}", object))
+            .collect();
+        assert_eq!(paths, expected);
+    }
+
+    #[tokio::test]
+    async fn test_download_objects_unordered() {
+        let objects: Vec<String> = (0..5).map(|i| format!("{}.txt", i)).collect();
+
+        let results = Storage::new("http://localhost")
+            .with_backend(Arc::new(ReverseBackend::new(5)))
+            .from()
+            .download_objects(
+                "thefux",
+                objects,
+                Concurrency {
+                    limit: 5,
+                    ordered: false,
+                    rate_limit: None,
+                },
+            )
+            .await;
+
+        let paths: Vec<String> = results
+            .into_iter()
+            .map(|result| String::from_utf8(result.unwrap().to_vec()).unwrap())
+            .collect();
+        let expected: Vec<String> = (0..5)
+            .rev()
+            .map(|i| format!("/object/thefux/{}.txt", i))
+            .collect();
+        assert_eq!(paths, expected);
+    }
+
+    #[tokio::test]
+    async fn test_download_objects_zero_limit() {
+        let backend = Arc::new(MockBackend::new());
+        let objects: Vec<String> = (0..3).map(|i| format!("{}.txt", i)).collect();
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(5),
+            Storage::new("http://localhost")
+                .with_backend(backend.clone())
+                .from()
+                .download_objects(
+                    "thefux",
+                    objects,
+                    Concurrency {
+                        limit: 0,
+                        ordered: true,
+                        rate_limit: None,
+                    },
+                ),
+        )
+        .await
+        .expect("a zero limit runs the requests one at a time");
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(backend.requests().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_download_objects_rate_limited() {
+        let backend = Arc::new(MockBackend::new());
+        let objects: Vec<String> = (0..6).map(|i| format!("{}.txt", i)).collect();
+
+        let start = Instant::now();
+        let results = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .download_objects(
+                "thefux",
+                objects,
+                Concurrency::new(6).rate_limit(RateLimiter::new(2.0).unwrap()),
+            )
+            .await;
+
+        // 2 requests are sent at once, the other 4 are spread over 2 seconds
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert_eq!(results.len(), 6);
+        assert_eq!(backend.requests().len(), 6);
+    }
+}
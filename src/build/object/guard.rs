@@ -0,0 +1,215 @@
+use reqwest::Response;
+
+use crate::{
+    build::{builder::Builder, executor::Executor},
+    model::errors,
+};
+
+/// Aborts a multipart or resumable upload unless it's marked as completed.
+///
+/// Dropping the guard before `complete` sends the abort request in the background, so an
+/// upload failing midway, e.g. returning early with `?`, doesn't leave a pending upload
+/// consuming quota. The request is only sent when dropped within a tokio runtime.
+pub struct AbortGuard {
+    abort: Option<Executor>,
+}
+
+impl AbortGuard {
+    /// Creates a guard sending the given abort request unless completed.
+    pub fn new(abort: Executor) -> Self {
+        Self { abort: Some(abort) }
+    }
+
+    /// Marks the upload as completed, the abort request isn't sent anymore.
+    pub fn complete(mut self) {
+        self.abort = None;
+    }
+
+    /// Aborts the upload now and waits for the response.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Response, errors::Error>` - The response of the abort request.
+    pub async fn abort(mut self) -> Result<Response, errors::Error> {
+        let abort = self
+            .abort
+            .take()
+            .expect("the guard is armed until consumed");
+        abort.execute_checked().await
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        if let Some(abort) = self.abort.take() {
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn(async move {
+                    let _ = abort.execute().await;
+                });
+            }
+        }
+    }
+}
+
+impl Builder {
+    /// guard an S3 compatible multipart upload, aborting it unless completed
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `upload_id` - the id returned by `initiate_multipart`
+    ///
+    /// # Returns
+    ///
+    /// * `AbortGuard` - The guard sending `abort_multipart` unless completed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::object::S3CompletedPart,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let guard = storage
+    ///         .from()
+    ///         .multipart_abort_guard("thefux", "large.bin", "<upload id>");
+    ///
+    ///     let part = storage
+    ///         .from()
+    ///         .upload_part("thefux", "large.bin", "<upload id>", 1, vec![0; 1024])
+    ///         .execute_checked()
+    ///         .await;
+    ///     // dropping the guard on error aborts the upload
+    ///     let etag = part.unwrap().headers()["etag"].to_str().unwrap().to_string();
+    ///
+    ///     storage
+    ///         .from()
+    ///         .complete_multipart("thefux", "large.bin", "<upload id>", &[S3CompletedPart {
+    ///             part_number: 1,
+    ///             etag,
+    ///         }])
+    ///         .execute_checked()
+    ///         .await
+    ///         .unwrap();
+    ///     guard.complete();
+    /// }
+    /// ```
+    pub fn multipart_abort_guard(
+        self,
+        bucket_id: &str,
+        object: &str,
+        upload_id: &str,
+    ) -> AbortGuard {
+        AbortGuard::new(self.abort_multipart(bucket_id, object, upload_id))
+    }
+
+    /// guard a resumable upload, terminating it unless completed
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The url returned in the `Location` header by `create_resumable_upload`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AbortGuard, errors::Error>` - The guard sending `terminate_resumable_upload`
+    ///   unless completed, or an `InvalidUploadUrl` error if `upload_url` can't be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let guard = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .resumable_abort_guard("<location>")
+    ///     .unwrap();
+    /// guard.complete();
+    /// # }
+    /// ```
+    pub fn resumable_abort_guard(self, upload_url: &str) -> Result<AbortGuard, errors::Error> {
+        Ok(AbortGuard::new(
+            self.terminate_resumable_upload(upload_url)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use reqwest::Method;
+
+    use crate::{testing::MockBackend, Storage};
+
+    #[tokio::test]
+    async fn test_abort_guard_dropped_after_failed_part() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            500,
+            &[],
+            r#"{"statusCode":"500","error":"internal","message":""}"#,
+        );
+        backend.push_response(204, &[], "");
+        let storage = Storage::new("http://localhost/storage/v1").with_backend(backend.clone());
+
+        let upload = async {
+            let _guard = storage
+                .from()
+                .multipart_abort_guard("thefux", "large.bin", "upload-1");
+            storage
+                .from()
+                .upload_part("thefux", "large.bin", "upload-1", 1, vec![0; 16])
+                .execute_checked()
+                .await?;
+            Ok::<_, crate::model::errors::Error>(())
+        };
+        assert!(upload.await.is_err());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, Method::DELETE);
+        assert_eq!(
+            requests[1].url.as_str(),
+            "http://localhost/storage/v1/s3/thefux/large.bin?uploadId=upload-1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abort_guard_explicit_abort_and_complete() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(204, &[], "");
+        let storage = Storage::new("http://localhost/storage/v1").with_backend(backend.clone());
+
+        let response = storage
+            .from()
+            .resumable_abort_guard("/storage/v1/upload/resumable/abc")
+            .unwrap()
+            .abort()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+
+        storage
+            .from()
+            .resumable_abort_guard("/storage/v1/upload/resumable/def")
+            .unwrap()
+            .complete();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::DELETE);
+        assert_eq!(requests[0].url.path(), "/storage/v1/upload/resumable/abc");
+        assert_eq!(requests[0].headers.get("tus-resumable").unwrap(), "1.0.0");
+    }
+}
@@ -1,8 +1,12 @@
 use reqwest::{header::HeaderValue, Method};
 
-use crate::build::{
-    builder::{BodyType, Builder},
-    executor::Executor,
+use crate::{
+    build::{
+        builder::{BodyType, Builder},
+        executor::Executor,
+        object::LIST_PAGE_SIZE,
+    },
+    model::options::{ListObjectsAfterOptions, ListObjectsOptions},
 };
 
 impl Builder {
@@ -53,6 +57,7 @@ impl Builder {
             .unwrap()
             .insert("Content-Type", HeaderValue::from_static("application/json"));
         self.method = Method::POST;
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -63,6 +68,167 @@ impl Builder {
         self.body = Some(BodyType::StringBody(body.to_string()));
         self.create_executor()
     }
+
+    /// list all files within a bucket using a struct
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `options` - The `ListObjectsOptions` struct containing the request body.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::{ListObjectsOptions, SortBy, SortColumn, SortOrder},
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .list_objects_from("thefux", ListObjectsOptions {
+    ///             limit: Some(100),
+    ///             offset: Some(0),
+    ///             sort_by: Some(SortBy {
+    ///                 column: SortColumn::Name,
+    ///                 order: SortOrder::Asc,
+    ///             }),
+    ///             ..ListObjectsOptions::new("folder")
+    ///         })
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn list_objects_from(self, bucket_id: &str, options: ListObjectsOptions) -> Executor {
+        self.list_objects(
+            bucket_id,
+            &serde_json::to_string(&options).unwrap_or_default(),
+        )
+    }
+
+    /// list the first page of files at the root of a bucket,
+    /// using prefix `""`, limit `100` and offset `0`
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .list_objects_all_default("thefux")
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn list_objects_all_default(self, bucket_id: &str) -> Executor {
+        self.list_objects_from(
+            bucket_id,
+            ListObjectsOptions {
+                limit: Some(LIST_PAGE_SIZE),
+                offset: Some(0),
+                ..ListObjectsOptions::new("")
+            },
+        )
+    }
+
+    /// list the files of a bucket following the page of `cursor`
+    ///
+    /// Uses the keyset pagination of the `list-v2` endpoint instead of an offset, the server
+    /// seeks to the cursor rather than skipping the objects before it, which keeps large buckets
+    /// fast to page through. The response is a `ListObjectsPage`, pass its opaque `next_cursor`
+    /// to get the next page.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `prefix` - The folder path.
+    /// * `cursor` - The `next_cursor` of the previous `ListObjectsPage`, `None` for the first page.
+    /// * `limit` - The number of files to return.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig, model::object::ListObjectsPage};
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let page = storage
+    ///         .from()
+    ///         .list_objects_after("thefux", "folder", None, 100)
+    ///         .execute_from::<ListObjectsPage>()
+    ///         .await
+    ///         .unwrap();
+    ///     if let Some(cursor) = page.next_cursor {
+    ///         let next = storage
+    ///             .from()
+    ///             .list_objects_after("thefux", "folder", Some(&cursor), 100)
+    ///             .execute_from::<ListObjectsPage>()
+    ///             .await
+    ///             .unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn list_objects_after(
+        mut self,
+        bucket_id: &str,
+        prefix: &str,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Executor {
+        let options = ListObjectsAfterOptions {
+            prefix: prefix.to_string(),
+            limit,
+            cursor: cursor.map(str::to_string),
+        };
+        self.headers
+            .lock()
+            .unwrap()
+            .insert("Content-Type", HeaderValue::from_static("application/json"));
+        self.method = Method::POST;
+        let bucket_id = self.bucket_id(bucket_id);
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .push("object")
+            .push("list-v2")
+            .push(bucket_id);
+
+        self.body = Some(BodyType::StringBody(
+            serde_json::to_string(&options).unwrap_or_default(),
+        ));
+        self.create_executor()
+    }
 }
 
 #[cfg(test)]
@@ -71,14 +237,17 @@ mod test {
     use std::sync::{Arc, Mutex};
     use url::{Host, Origin};
 
-    use crate::build::builder::{BodyType, Builder};
+    use crate::{
+        build::builder::{BodyType, Builder},
+        model::{object::ListObjectsPage, options::ListObjectsOptions},
+    };
 
     #[test]
     fn test_list_objects() {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .list_objects("test_bucket", r#"{"test": "body"}"#);
 
@@ -106,4 +275,81 @@ mod test {
             Origin::Tuple("http".into(), Host::Domain("localhost".into()), 80)
         );
     }
+
+    #[test]
+    fn test_list_objects_from() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .list_objects_from("test_bucket", ListObjectsOptions::new("folder"));
+
+        assert_eq!(executor.builder.url.path(), "/object/list/test_bucket");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"prefix":"folder"}"#),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_list_objects_all_default() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .list_objects_all_default("test_bucket");
+
+        assert_eq!(executor.builder.url.path(), "/object/list/test_bucket");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => {
+                assert_eq!(val, r#"{"prefix":"","limit":100,"offset":0}"#)
+            }
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_list_objects_after() {
+        let builder = || {
+            Builder::new(
+                url::Url::parse("http://localhost").unwrap(),
+                Arc::new(Mutex::new(HeaderMap::new())),
+                Client::new(),
+            )
+        };
+
+        let executor = builder().list_objects_after("test_bucket", "folder", None, 50);
+        assert_eq!(executor.builder.url.path(), "/object/list-v2/test_bucket");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => {
+                assert_eq!(val, r#"{"prefix":"folder","limit":50}"#)
+            }
+            _ => panic!("nop"),
+        }
+
+        let page: ListObjectsPage = serde_json::from_str(
+            r#"{
+                "hasNext": true,
+                "folders": [{"name": "folder/sub"}],
+                "objects": [{"name": "folder/b.pdf", "id": "1f3a"}],
+                "nextCursor": "Zm9sZGVyL2IucGRm"
+            }"#,
+        )
+        .unwrap();
+        assert!(page.has_next);
+        assert_eq!(page.folders[0].name, "folder/sub");
+        assert_eq!(page.objects[0].name, "folder/b.pdf");
+
+        let executor =
+            builder().list_objects_after("test_bucket", "folder", page.next_cursor.as_deref(), 50);
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(
+                val,
+                r#"{"prefix":"folder","limit":50,"cursor":"Zm9sZGVyL2IucGRm"}"#
+            ),
+            _ => panic!("nop"),
+        }
+    }
 }
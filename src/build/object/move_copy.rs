@@ -1,9 +1,8 @@
-use reqwest::header::HeaderValue;
 use reqwest::Method;
 
-use crate::build::builder::BodyType;
 use crate::build::executor::Executor;
-use crate::model::object::MoveCopyObject;
+use crate::model::errors;
+use crate::model::object::{MoveCopyObject, Response};
 use crate::Builder;
 
 enum Action {
@@ -21,23 +20,18 @@ impl From<Action> for &str {
 }
 
 impl Builder {
-    pub(crate) fn action_intern(mut self, move_obj: MoveCopyObject, action: &str) -> Executor {
-        self.headers
-            .lock()
-            .unwrap()
-            .insert("Content-Type", HeaderValue::from_static("application/json"));
-        self.method = Method::POST;
-        self.url
+    pub(crate) fn action_intern(mut self, mut move_obj: MoveCopyObject, action: &str) -> Executor {
+        move_obj.bucket_id = self.bucket_id(&move_obj.bucket_id).to_string();
+        let mut builder = self.model_body(&move_obj);
+        builder.method = Method::POST;
+        builder
+            .url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push(action);
 
-        self.body = Some(BodyType::StringBody(
-            serde_json::to_string(&move_obj).unwrap(),
-        ));
-
-        self.create_executor()
+        builder.create_executor()
     }
 
     fn action_intern_from(self, move_obj: MoveCopyObject, action: &str) -> Executor {
@@ -55,6 +49,7 @@ impl Builder {
             bucket_id: bucket_id.to_string(),
             source_key: from.to_string(),
             destination_key: to.to_string(),
+            copy_metadata: None,
         };
 
         self.action_intern(move_body, action)
@@ -77,7 +72,7 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
+    ///     model::object::Response,
     /// };
     /// use dotenv::dotenv;
     ///
@@ -88,7 +83,7 @@ impl Builder {
     ///     let response = Storage::new_with_config(config)
     ///         .from()
     ///         .move_object("thefux", "from", "to")
-    ///         .execute()
+    ///         .execute_from::<Response>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -114,8 +109,7 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
-    ///     model::object::MoveCopyObject,
+    ///     model::object::{MoveCopyObject, Response},
     /// };
     /// use dotenv::dotenv;
     ///
@@ -127,11 +121,12 @@ impl Builder {
     ///         bucket_id: "thefux".to_string(),
     ///         source_key: "from".to_string(),
     ///         destination_key: "to".to_string(),
+    ///         copy_metadata: None,
     ///     };
     ///     let response = Storage::new_with_config(config)
     ///         .from()
     ///         .move_object_from(move_obj)
-    ///         .execute()
+    ///         .execute_from::<Response>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -157,7 +152,7 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
+    ///     model::object::Response,
     /// };
     /// use dotenv::dotenv;
     ///
@@ -168,7 +163,7 @@ impl Builder {
     ///     let response = Storage::new_with_config(config)
     ///         .from()
     ///         .copy_object("thefux", "from", "to")
-    ///         .execute()
+    ///         .execute_from::<Response>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -194,8 +189,7 @@ impl Builder {
     /// use supabase_storage::{
     ///     Storage,
     ///     config::SupabaseConfig,
-    ///     model::bucket::NewBucket,
-    ///     model::object::MoveCopyObject,
+    ///     model::object::{MoveCopyObject, Response},
     /// };
     /// use dotenv::dotenv;
     ///
@@ -207,11 +201,12 @@ impl Builder {
     ///         bucket_id: "thefux".to_string(),
     ///         source_key: "from".to_string(),
     ///         destination_key: "to".to_string(),
+    ///         copy_metadata: Some(true),
     ///     };
     ///     let response = Storage::new_with_config(config)
     ///         .from()
     ///         .copy_object_from(move_obj)
-    ///         .execute()
+    ///         .execute_from::<Response>()
     ///         .await
     ///         .unwrap();
     /// }
@@ -219,6 +214,94 @@ impl Builder {
     pub fn copy_object_from(self, obj: MoveCopyObject) -> Executor {
         self.action_intern_from(obj, Action::Copy.into())
     }
+
+    /// move an object and return the key of the moved object
+    ///
+    /// The storage api only answers with a message, the key is the destination of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `from` - object soruce
+    /// * `to` - object destination
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, errors::Error>` - The key of the moved object.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let key = Storage::new_with_config(config)
+    ///         .from()
+    ///         .move_object_returning_key("thefux", "from", "to")
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn move_object_returning_key(
+        self,
+        bucket_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<String, errors::Error> {
+        self.move_object(bucket_id, from, to)
+            .execute_from::<Response>()
+            .await?;
+        Ok(to.to_string())
+    }
+
+    /// copy an object and return the key of the copy
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `from` - object soruce
+    /// * `to` - object destination
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, errors::Error>` - The key of the copied object.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let key = Storage::new_with_config(config)
+    ///         .from()
+    ///         .copy_object_returning_key("thefux", "from", "to")
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn copy_object_returning_key(
+        self,
+        bucket_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<String, errors::Error> {
+        self.copy_object(bucket_id, from, to)
+            .execute_from::<serde_json::Value>()
+            .await?;
+        Ok(to.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -231,6 +314,8 @@ mod test {
     use crate::{
         build::builder::{BodyType, Builder},
         model::object::MoveCopyObject,
+        testing::MockBackend,
+        Storage,
     };
 
     #[test]
@@ -238,7 +323,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .copy_object("thefux", "from", "to");
 
@@ -276,7 +361,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .move_object("thefux", "from", "to");
 
@@ -288,12 +373,13 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .move_object_from(MoveCopyObject {
             bucket_id: "thefux".to_string(),
             source_key: "from".to_string(),
             destination_key: "to".to_string(),
+            copy_metadata: None,
         });
 
         if let Some(typ) = executor.builder.body {
@@ -313,14 +399,60 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .copy_object_from(MoveCopyObject {
             bucket_id: "thefux".to_string(),
             source_key: "from".to_string(),
             destination_key: "to".to_string(),
+            copy_metadata: None,
         });
 
         assert_eq!(executor.builder.url.path(), "/object/copy");
     }
+
+    #[test]
+    fn test_copy_object_from_with_metadata() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .copy_object_from(MoveCopyObject {
+            bucket_id: "thefux".to_string(),
+            source_key: "from".to_string(),
+            destination_key: "to".to_string(),
+            copy_metadata: Some(false),
+        });
+
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(
+                val,
+                r#"{"bucketId":"thefux","sourceKey":"from","destinationKey":"to","copyMetadata":false}"#
+            ),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_move_object_returning_key() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], r#"{"message":"Successfully moved"}"#);
+        backend.push_response(200, &[], r#"{"Key":"thefux/copy/to.pdf"}"#);
+        let storage = Storage::new("http://localhost").with_backend(backend.clone());
+
+        let key = storage
+            .from()
+            .move_object_returning_key("thefux", "from.pdf", "moved/to.pdf")
+            .await
+            .unwrap();
+        assert_eq!(key, "moved/to.pdf");
+
+        let key = storage
+            .from()
+            .copy_object_returning_key("thefux", "from.pdf", "copy/to.pdf")
+            .await
+            .unwrap();
+        assert_eq!(key, "copy/to.pdf");
+    }
 }
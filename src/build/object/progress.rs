@@ -0,0 +1,150 @@
+use std::{future::Future, io};
+
+use futures::{channel::mpsc, Stream, StreamExt};
+use reqwest::{Body, Method, Response};
+use tokio::fs::File;
+use tokio_util::codec::{BytesCodec, FramedRead};
+
+use crate::{
+    build::builder::Builder,
+    model::{errors, object::Progress, options::FileOptions},
+};
+
+impl Builder {
+    /// upload an object, reporting the progress of the upload as a stream
+    ///
+    /// The stream yields a `Progress` for every chunk of the file handed to the connection
+    /// and ends once the upload finished. The upload only makes progress while the
+    /// returned future is polled, e.g. alongside the stream in `tokio::select!`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(impl Future, impl Stream<Item = Progress>), io::Error>` - The upload and its progress,
+    ///   or the error opening the file.
+    ///
+    /// # Example
+    /// ```
+    /// use futures::StreamExt;
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let (upload, progress) = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_object_progress_stream("thefux", "btc.pdf", "out/test.pdf", FileOptions {
+    ///             cache_control: None,
+    ///             content_type: None,
+    ///             upsert: Some(true),
+    ///             content_disposition: None,
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let progress = progress.for_each(|progress| async move {
+    ///         println!("{}/{}", progress.bytes_sent, progress.total);
+    ///     });
+    ///     let (response, _) = tokio::join!(upload, progress);
+    ///     println!("{:?}", response);
+    /// }
+    /// ```
+    pub async fn upload_object_progress_stream(
+        mut self,
+        bucket_name: &str,
+        object: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<
+        (
+            impl Future<Output = Result<Response, errors::Error>>,
+            impl Stream<Item = Progress>,
+        ),
+        io::Error,
+    > {
+        let file = File::open(file_path).await?;
+        let total = file
+            .metadata()
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+
+        let (sender, receiver) = mpsc::unbounded();
+        let mut bytes_sent = 0;
+        let stream = FramedRead::new(file, BytesCodec::new()).inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                bytes_sent += chunk.len() as u64;
+                // the receiver may have been dropped, progress is best effort
+                let _ = sender.unbounded_send(Progress { bytes_sent, total });
+            }
+        });
+
+        self.method = Method::POST;
+        let mut executor = self.shared_upload_body(bucket_name, object, Body::wrap_stream(stream));
+        executor.builder.apply_file_options(&file_options);
+
+        Ok((executor.execute(), receiver))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+
+    use crate::{model::options::FileOptions, Storage};
+
+    #[tokio::test]
+    async fn test_upload_object_progress_stream() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path();
+        tokio::fs::write(path, vec![7u8; 256 * 1024]).await.unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/object/thefux/progress.bin")
+            .match_header("x-upsert", "true")
+            .with_body(r#"{"Key":"thefux/progress.bin"}"#)
+            .create_async()
+            .await;
+
+        let (upload, progress) = Storage::new(server.url())
+            .from()
+            .upload_object_progress_stream(
+                "thefux",
+                "progress.bin",
+                path.to_str().unwrap(),
+                FileOptions {
+                    cache_control: None,
+                    content_type: None,
+                    upsert: Some(true),
+                    content_disposition: None,
+                },
+            )
+            .await
+            .unwrap();
+        let (response, progress) = tokio::join!(upload, progress.collect::<Vec<_>>());
+
+        assert_eq!(response.unwrap().status(), 200);
+        mock.assert_async().await;
+
+        assert!(progress.len() > 1);
+        assert!(progress
+            .windows(2)
+            .all(|pair| pair[0].bytes_sent < pair[1].bytes_sent));
+        let last = progress.last().unwrap();
+        assert_eq!(last.bytes_sent, 256 * 1024);
+        assert_eq!(last.total, 256 * 1024);
+    }
+}
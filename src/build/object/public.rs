@@ -1,4 +1,7 @@
-use crate::build::{builder::Builder, executor::Executor};
+use crate::build::{
+    builder::{object_segments, Builder},
+    executor::Executor,
+};
 
 impl Builder {
     /// get public object from the storage
@@ -34,16 +37,65 @@ impl Builder {
     /// }
     /// ```
     pub fn get_public_object(mut self, bucket_id: &str, object: &str) -> Executor {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push("public")
             .push(bucket_id)
-            .push(object);
+            .extend(object_segments(object));
         self.create_executor()
     }
 
+    /// get public object from the storage, forcing the browser to download it
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - a wildcard
+    /// * `filename` - the name of the downloaded file, defaults to the object name
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_public_object_download("thefux", "file_name.pdf", Some("report.pdf".to_string()))
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn get_public_object_download(
+        self,
+        bucket_id: &str,
+        object: &str,
+        filename: Option<String>,
+    ) -> Executor {
+        let mut executor = self.get_public_object(bucket_id, object);
+        let mut query = executor.builder.url.query_pairs_mut();
+        match filename {
+            Some(filename) => query.append_pair("download", &filename),
+            None => query.append_key_only("download"),
+        };
+        drop(query);
+        executor
+    }
+
     /// get public object info
     ///
     /// # Arguments
@@ -77,6 +129,7 @@ impl Builder {
     /// }
     /// ```
     pub fn get_public_object_info(mut self, bucket_id: &str, object: &str) -> Executor {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -84,7 +137,7 @@ impl Builder {
             .push("info")
             .push("public")
             .push(bucket_id)
-            .push(object);
+            .extend(object_segments(object));
         self.create_executor()
     }
 }
@@ -103,7 +156,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .get_public_object("thefux", "test.pdf");
 
@@ -123,7 +176,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .get_public_object_info("thefux", "test.pdf");
 
@@ -137,4 +190,31 @@ mod test {
             "/object/info/public/thefux/test.pdf"
         );
     }
+
+    #[test]
+    fn test_get_public_object_download() {
+        let builder = || {
+            Builder::new(
+                url::Url::parse("http://localhost").unwrap(),
+                Arc::new(Mutex::new(HeaderMap::new())),
+                Client::new(),
+            )
+        };
+
+        let executor = builder().get_public_object_download("thefux", "test.pdf", None);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/object/public/thefux/test.pdf?download"
+        );
+
+        let executor = builder().get_public_object_download(
+            "thefux",
+            "test.pdf",
+            Some("my report.pdf".to_string()),
+        );
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/object/public/thefux/test.pdf?download=my+report.pdf"
+        );
+    }
 }
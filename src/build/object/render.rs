@@ -1,11 +1,19 @@
+use reqwest::header::ACCEPT;
+
 use crate::{
-    build::{builder::Builder, executor::Executor},
-    model::options::Transform,
+    build::{
+        builder::{object_segments, Builder},
+        executor::Executor,
+    },
+    model::{errors, options::Transform},
 };
 
 impl Builder {
     /// get public object from the storage
     ///
+    /// To store the transformed image, pipe `execute_reader` into `upload_object_stream`, the
+    /// rendered image is uploaded while it's downloaded without being buffered.
+    ///
     /// # Arguments
     ///
     /// * `bucket_id` - bucket id
@@ -50,6 +58,7 @@ impl Builder {
         object: &str,
         transform: Transform,
     ) -> Executor {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -57,13 +66,121 @@ impl Builder {
             .push("image")
             .push("authenticated")
             .push(bucket_id)
-            .push(object);
+            .extend(object_segments(object));
 
         self.url
             .set_query(Some(&serde_qs::to_string(&transform).unwrap()));
 
         self.create_executor()
     }
+
+    /// get object with transformation, rejecting objects which are not images
+    ///
+    /// The check is based on the extension of the object, objects with an unknown
+    /// extension are accepted. Use `get_object_with_transform` to skip the check,
+    /// e.g. if the extension doesn't match the content.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name/path
+    /// * `transform` - tranformation options to transform before serving it to client
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or a `NotAnImage` error.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     model::errors::ErrorKind,
+    ///     model::options::{Transform, Format},
+    /// };
+    ///
+    /// let error = Storage::new("http://localhost")
+    ///     .from()
+    ///     .try_get_object_with_transform("thefux", "test.pdf", Transform {
+    ///         format: Some(Format::Webp),
+    ///         height: None,
+    ///         quality: None,
+    ///         resize: None,
+    ///         width: None,
+    ///     })
+    ///     .err()
+    ///     .unwrap();
+    /// assert_eq!(error.kind(), ErrorKind::NotAnImage);
+    /// ```
+    pub fn try_get_object_with_transform(
+        self,
+        bucket_id: &str,
+        object: &str,
+        transform: Transform,
+    ) -> Result<Executor, errors::Error> {
+        if let Some(mime) = mime_guess::from_path(object).first() {
+            if mime.type_() != mime_guess::mime::IMAGE {
+                return Err(errors::Error {
+                    status_code: "400".to_string(),
+                    error: "NotAnImage".to_string(),
+                    message: format!("{} is not an image ({})", object, mime),
+                    context: None,
+                });
+            }
+        }
+
+        Ok(self.get_object_with_transform(bucket_id, object, transform))
+    }
+
+    /// get object with transformation, negotiating the image format via the `Accept` header
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name/path
+    /// * `transform` - tranformation options to transform before serving it to client
+    /// * `accept` - value of the `Accept` header, e.g. `image/webp`
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::{Transform, Resize}
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .get_object_with_transform_accept("thefux", "test.png", Transform {
+    ///             format: None,
+    ///             height: Some(100),
+    ///             quality: None,
+    ///             resize: Some(Resize::Cover),
+    ///             width: Some(100),
+    ///         }, "image/webp")
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn get_object_with_transform_accept(
+        self,
+        bucket_id: &str,
+        object: &str,
+        transform: Transform,
+        accept: &str,
+    ) -> Executor {
+        self.checked_header(ACCEPT, accept)
+            .get_object_with_transform(bucket_id, object, transform)
+    }
 }
 
 #[cfg(test)]
@@ -74,7 +191,10 @@ mod test {
 
     use crate::{
         build::builder::BodyType,
-        model::options::{Format, Resize},
+        model::{
+            errors::ErrorKind,
+            options::{Format, Resize},
+        },
     };
 
     use super::*;
@@ -84,7 +204,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .get_object_with_transform(
             "thefux",
@@ -125,4 +245,124 @@ mod test {
             "/render/image/authenticated/thefux/test.png"
         );
     }
+
+    #[test]
+    fn test_get_object_with_transform_accept() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .get_object_with_transform_accept(
+            "thefux",
+            "test.png",
+            Transform {
+                format: Some(Format::Origin),
+                height: Some(100),
+                quality: None,
+                resize: None,
+                width: None,
+            },
+            "image/webp",
+        );
+
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("Accept")
+                .unwrap(),
+            "image/webp"
+        );
+        assert_eq!(
+            executor.builder.url.path(),
+            "/render/image/authenticated/thefux/test.png"
+        );
+        assert_eq!(
+            executor.builder.url.query(),
+            Some("format=origin&height=100")
+        );
+    }
+
+    #[test]
+    fn test_get_object_with_transform_invalid_accept() {
+        let error = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .get_object_with_transform_accept(
+            "thefux",
+            "test.png",
+            Transform::webp_optimized(),
+            "image/webp\r\n",
+        )
+        .dry_run()
+        .unwrap_err();
+
+        assert_eq!(error.error, "InvalidHeader");
+    }
+
+    #[test]
+    fn test_try_get_object_with_transform_not_an_image() {
+        let builder = || {
+            Builder::new(
+                url::Url::parse("http://localhost").unwrap(),
+                Arc::new(Mutex::new(HeaderMap::new())),
+                Client::new(),
+            )
+        };
+        let transform = || Transform {
+            format: Some(Format::Webp),
+            height: None,
+            quality: None,
+            resize: None,
+            width: None,
+        };
+
+        let result = builder().try_get_object_with_transform("thefux", "test.pdf", transform());
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::NotAnImage);
+
+        let result = builder().try_get_object_with_transform("thefux", "test.png", transform());
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pipe_rendered_image_into_upload() {
+        let mut server = mockito::Server::new_async().await;
+        let render = server
+            .mock("GET", "/render/image/authenticated/thefux/cat.png")
+            .match_query("format=webp&quality=75")
+            .with_header("content-type", "image/webp")
+            .with_body("rendered webp")
+            .create_async()
+            .await;
+        let upload = server
+            .mock("POST", "/object/thefux/cat.webp")
+            .match_header("content-type", "image/webp")
+            .match_body("rendered webp")
+            .with_body(r#"{"Key":"thefux/cat.webp"}"#)
+            .create_async()
+            .await;
+
+        let storage = crate::Storage::new(server.url());
+        let rendered = storage
+            .from()
+            .get_object_with_transform("thefux", "cat.png", Transform::webp_optimized())
+            .execute_reader()
+            .await
+            .unwrap();
+        let response = storage
+            .from()
+            .upload_object_stream("thefux", "cat.webp", rendered)
+            .execute_checked()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        render.assert_async().await;
+        upload.assert_async().await;
+    }
 }
@@ -0,0 +1,282 @@
+use reqwest::{header::HeaderValue, Body, Method};
+use url::Url;
+
+use crate::{
+    build::{
+        builder::{BodyType, Builder},
+        executor::Executor,
+    },
+    model::{errors, options::ResumableUploadOptions},
+};
+
+/// version of the TUS protocol spoken by the storage api
+const TUS_VERSION: &str = "1.0.0";
+
+impl Builder {
+    fn tus_header(&mut self, name: &'static str, value: &str) {
+        self.headers
+            .lock()
+            .unwrap()
+            .insert(name, HeaderValue::from_str(value).unwrap());
+    }
+
+    /// Resolves the `Location` of a resumable upload against the storage url.
+    fn resumable_upload_url(&self, upload_url: &str) -> Result<Url, errors::Error> {
+        self.url.join(upload_url).map_err(|error| errors::Error {
+            status_code: "400".to_string(),
+            error: "InvalidUploadUrl".to_string(),
+            message: format!("{} is not a valid upload url: {}", upload_url, error),
+            context: None,
+        })
+    }
+
+    /// create a resumable upload using the TUS protocol
+    ///
+    /// The url of the created upload is returned in the `Location` header of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The `ResumableUploadOptions` describing the object.
+    /// * `upload_length` - The size of the object in bytes.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::ResumableUploadOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .create_resumable_upload(&ResumableUploadOptions::new("thefux", "btc.pdf"), 1024)
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    ///     let location = response.headers().get("location");
+    /// }
+    /// ```
+    pub fn create_resumable_upload(
+        mut self,
+        options: &ResumableUploadOptions,
+        upload_length: u64,
+    ) -> Executor {
+        self.method = Method::POST;
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .push("upload")
+            .push("resumable");
+
+        self.tus_header("tus-resumable", TUS_VERSION);
+        self.tus_header("upload-length", &upload_length.to_string());
+        self.tus_header("upload-metadata", &options.upload_metadata());
+        if let Some(upsert) = options.upsert {
+            self.tus_header("x-upsert", &upsert.to_string());
+        }
+
+        self.create_executor()
+    }
+
+    /// upload a chunk of a resumable upload
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The url returned in the `Location` header by `create_resumable_upload`.
+    /// * `offset` - The offset of the chunk in bytes.
+    /// * `chunk` - The bytes to upload.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or an
+    ///   `InvalidUploadUrl` error if `upload_url` can't be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .upload_resumable_chunk("<location>", 0, b"hello".to_vec())
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn upload_resumable_chunk(
+        mut self,
+        upload_url: &str,
+        offset: u64,
+        chunk: Vec<u8>,
+    ) -> Result<Executor, errors::Error> {
+        self.method = Method::PATCH;
+        self.url = self.resumable_upload_url(upload_url)?;
+
+        self.tus_header("tus-resumable", TUS_VERSION);
+        self.tus_header("upload-offset", &offset.to_string());
+        self.tus_header("content-type", "application/offset+octet-stream");
+        self.body = Some(BodyType::ReqwestBody(Body::from(chunk)));
+
+        Ok(self.create_executor())
+    }
+
+    /// terminate a resumable upload, discarding the uploaded chunks
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The url returned in the `Location` header by `create_resumable_upload`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The constructed `Executor` instance, or an
+    ///   `InvalidUploadUrl` error if `upload_url` can't be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .terminate_resumable_upload("/storage/v1/upload/resumable/abc")
+    ///     .unwrap();
+    /// assert_eq!(executor.url().path(), "/storage/v1/upload/resumable/abc");
+    /// ```
+    pub fn terminate_resumable_upload(
+        mut self,
+        upload_url: &str,
+    ) -> Result<Executor, errors::Error> {
+        self.method = Method::DELETE;
+        self.url = self.resumable_upload_url(upload_url)?;
+
+        self.tus_header("tus-resumable", TUS_VERSION);
+
+        Ok(self.create_executor())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::{header::HeaderMap, Client, Method};
+    use std::sync::{Arc, Mutex};
+
+    use crate::{build::builder::Builder, model::options::ResumableUploadOptions};
+
+    fn builder() -> Builder {
+        Builder::new(
+            url::Url::parse("http://localhost/storage/v1").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+    }
+
+    #[test]
+    fn test_create_resumable_upload() {
+        let options = ResumableUploadOptions {
+            content_type: Some("application/pdf".to_string()),
+            upsert: Some(true),
+            ..ResumableUploadOptions::new("thefux", "btc.pdf")
+        };
+        let executor = builder().create_resumable_upload(&options, 1024);
+
+        assert_eq!(executor.builder.method, Method::POST);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/storage/v1/upload/resumable"
+        );
+
+        let headers = executor.builder.headers.lock().unwrap();
+        assert_eq!(headers.get("tus-resumable").unwrap(), "1.0.0");
+        assert_eq!(headers.get("upload-length").unwrap(), "1024");
+        assert_eq!(headers.get("x-upsert").unwrap(), "true");
+        assert_eq!(
+            headers.get("upload-metadata").unwrap(),
+            "bucketName dGhlZnV4,objectName YnRjLnBkZg==,contentType YXBwbGljYXRpb24vcGRm"
+        );
+    }
+
+    #[test]
+    fn test_upload_resumable_chunk() {
+        let executor = builder()
+            .upload_resumable_chunk(
+                "http://localhost/storage/v1/upload/resumable/abc",
+                5,
+                b"hello".to_vec(),
+            )
+            .unwrap();
+
+        assert_eq!(executor.builder.method, Method::PATCH);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/storage/v1/upload/resumable/abc"
+        );
+
+        let headers = executor.builder.headers.lock().unwrap();
+        assert_eq!(headers.get("tus-resumable").unwrap(), "1.0.0");
+        assert_eq!(headers.get("upload-offset").unwrap(), "5");
+        assert_eq!(
+            headers.get("content-type").unwrap(),
+            "application/offset+octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_upload_resumable_chunk_invalid_url() {
+        let error = builder()
+            .upload_resumable_chunk("http://[storage/upload", 0, b"hello".to_vec())
+            .err()
+            .unwrap();
+
+        assert_eq!(error.error, "InvalidUploadUrl");
+    }
+
+    #[test]
+    fn test_terminate_resumable_upload() {
+        let executor = builder()
+            .terminate_resumable_upload("http://localhost/storage/v1/upload/resumable/abc")
+            .unwrap();
+
+        assert_eq!(executor.builder.method, Method::DELETE);
+        assert_eq!(
+            executor.builder.url.as_str(),
+            "http://localhost/storage/v1/upload/resumable/abc"
+        );
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("tus-resumable")
+                .unwrap(),
+            "1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_terminate_resumable_upload_invalid_url() {
+        let error = builder()
+            .terminate_resumable_upload("http://[storage/upload")
+            .err()
+            .unwrap();
+
+        assert_eq!(error.error, "InvalidUploadUrl");
+    }
+}
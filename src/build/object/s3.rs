@@ -0,0 +1,461 @@
+use reqwest::{header::HeaderValue, Body, Method};
+use serde::Serialize;
+
+use crate::{
+    build::{
+        builder::{object_segments, BodyType, Builder},
+        executor::{error_from_response, Executor},
+    },
+    model::{
+        errors,
+        object::{S3CompletedPart, S3ListObjects},
+    },
+};
+
+#[derive(Serialize)]
+#[serde(rename = "CompleteMultipartUpload")]
+struct CompleteMultipartUpload<'a> {
+    #[serde(rename = "Part")]
+    parts: &'a [S3CompletedPart],
+}
+
+impl Builder {
+    fn s3_object_url(&mut self, bucket_id: &str, object: &str) {
+        let bucket_id = self.bucket_id(bucket_id);
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .push("s3")
+            .push(bucket_id)
+            .extend(object_segments(object));
+    }
+    /// list objects using the S3 compatible `ListObjectsV2` api
+    ///
+    /// Pages are requested with the continuation token of the previous page instead of a
+    /// growing offset, which stays cheap for very large buckets.
+    ///
+    /// The S3 endpoint expects requests signed with AWS Signature Version 4, which this
+    /// crate doesn't implement. Sign the requests in a custom `HttpBackend` set with
+    /// `Storage::with_backend`, or use a gateway accepting the storage api keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `prefix` - only list the objects starting with the prefix
+    /// * `continuation_token` - `next_continuation_token` of the previous page, `None` for the first page
+    ///
+    /// # Returns
+    ///
+    /// * `Result<S3ListObjects, errors::Error>` - A page of objects along with the token of the next page.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let mut token = None;
+    ///     loop {
+    ///         let page = storage
+    ///             .from()
+    ///             .s3_list_objects("thefux", "invoices/", token.as_deref())
+    ///             .await
+    ///             .unwrap();
+    ///         println!("{:?}", page.contents);
+    ///         match page.next_continuation_token {
+    ///             Some(next) => token = Some(next),
+    ///             None => break,
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub async fn s3_list_objects(
+        mut self,
+        bucket_id: &str,
+        prefix: &str,
+        continuation_token: Option<&str>,
+    ) -> Result<S3ListObjects, errors::Error> {
+        let bucket_id = self.bucket_id(bucket_id);
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .push("s3")
+            .push(bucket_id);
+        {
+            let mut query = self.url.query_pairs_mut();
+            query
+                .append_pair("list-type", "2")
+                .append_pair("prefix", prefix);
+            if let Some(token) = continuation_token {
+                query.append_pair("continuation-token", token);
+            }
+        }
+
+        let limit = self.max_error_body_size;
+        let response = self.create_executor().execute().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(error_from_response(response, limit).await?);
+        }
+
+        let text = response.text().await?;
+        quick_xml::de::from_str(&text).map_err(|error| errors::Error {
+            status_code: status.as_str().to_string(),
+            error: "InvalidResponse".to_string(),
+            message: error.to_string(),
+            context: None,
+        })
+    }
+
+    /// initiate an S3 compatible multipart upload
+    ///
+    /// The response is an `InitiateMultipartUploadResult` xml document which deserializes into
+    /// `S3MultipartUpload`, e.g. with `quick_xml::de::from_str`. The requests must be signed,
+    /// see `s3_list_objects`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .initiate_multipart("thefux", "large.bin");
+    /// assert_eq!(executor.url().query(), Some("uploads"));
+    /// ```
+    pub fn initiate_multipart(mut self, bucket_id: &str, object: &str) -> Executor {
+        self.method = Method::POST;
+        self.s3_object_url(bucket_id, object);
+        self.url.query_pairs_mut().append_key_only("uploads");
+        self.create_executor()
+    }
+
+    /// upload a part of an S3 compatible multipart upload
+    ///
+    /// The `ETag` header of the response is needed to complete the upload.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `upload_id` - the id returned by `initiate_multipart`
+    /// * `part_number` - the number of the part, starting at 1
+    /// * `bytes` - the content of the part
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .upload_part("thefux", "large.bin", "<upload id>", 1, vec![0; 1024]);
+    /// ```
+    pub fn upload_part(
+        mut self,
+        bucket_id: &str,
+        object: &str,
+        upload_id: &str,
+        part_number: u32,
+        bytes: Vec<u8>,
+    ) -> Executor {
+        self.method = Method::PUT;
+        self.s3_object_url(bucket_id, object);
+        self.url
+            .query_pairs_mut()
+            .append_pair("partNumber", &part_number.to_string())
+            .append_pair("uploadId", upload_id);
+        self.body = Some(BodyType::ReqwestBody(Body::from(bytes)));
+        self.create_executor()
+    }
+
+    /// complete an S3 compatible multipart upload
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `upload_id` - the id returned by `initiate_multipart`
+    /// * `parts` - the uploaded parts along with their `ETag`
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, model::object::S3CompletedPart};
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .complete_multipart("thefux", "large.bin", "<upload id>", &[S3CompletedPart {
+    ///         part_number: 1,
+    ///         etag: "\"e5d1b1a0\"".to_string(),
+    ///     }]);
+    /// ```
+    pub fn complete_multipart(
+        mut self,
+        bucket_id: &str,
+        object: &str,
+        upload_id: &str,
+        parts: &[S3CompletedPart],
+    ) -> Executor {
+        self.method = Method::POST;
+        self.s3_object_url(bucket_id, object);
+        self.url
+            .query_pairs_mut()
+            .append_pair("uploadId", upload_id);
+        self.headers
+            .lock()
+            .unwrap()
+            .insert("Content-Type", HeaderValue::from_static("application/xml"));
+        let body = quick_xml::se::to_string(&CompleteMultipartUpload { parts })
+            .expect("CompleteMultipartUpload is always serializable");
+        self.body = Some(BodyType::StringBody(body));
+        self.create_executor()
+    }
+
+    /// abort an S3 compatible multipart upload, discarding the uploaded parts
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `upload_id` - the id returned by `initiate_multipart`
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let executor = Storage::new("http://localhost/storage/v1")
+    ///     .from()
+    ///     .abort_multipart("thefux", "large.bin", "<upload id>");
+    /// ```
+    pub fn abort_multipart(mut self, bucket_id: &str, object: &str, upload_id: &str) -> Executor {
+        self.method = Method::DELETE;
+        self.s3_object_url(bucket_id, object);
+        self.url
+            .query_pairs_mut()
+            .append_pair("uploadId", upload_id);
+        self.create_executor()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use reqwest::Method;
+
+    use crate::{
+        build::builder::BodyType,
+        model::object::{S3CompletedPart, S3MultipartUpload},
+        testing::MockBackend,
+        Storage,
+    };
+
+    fn storage() -> Storage {
+        Storage::new("http://localhost/storage/v1")
+    }
+
+    #[test]
+    fn test_initiate_multipart() {
+        let executor = storage().from().initiate_multipart("thefux", "a/large.bin");
+
+        assert_eq!(executor.builder.method, Method::POST);
+        assert_eq!(
+            executor.url().as_str(),
+            "http://localhost/storage/v1/s3/thefux/a/large.bin?uploads"
+        );
+
+        let upload: S3MultipartUpload = quick_xml::de::from_str(
+            r#"<InitiateMultipartUploadResult>
+  <Bucket>thefux</Bucket>
+  <Key>a/large.bin</Key>
+  <UploadId>upload-1</UploadId>
+</InitiateMultipartUploadResult>"#,
+        )
+        .unwrap();
+        assert_eq!(upload.bucket, "thefux");
+        assert_eq!(upload.key, "a/large.bin");
+        assert_eq!(upload.upload_id, "upload-1");
+    }
+
+    #[test]
+    fn test_upload_part() {
+        let executor =
+            storage()
+                .from()
+                .upload_part("thefux", "large.bin", "upload-1", 2, vec![1, 2, 3]);
+
+        assert_eq!(executor.builder.method, Method::PUT);
+        assert_eq!(
+            executor.url().as_str(),
+            "http://localhost/storage/v1/s3/thefux/large.bin?partNumber=2&uploadId=upload-1"
+        );
+        match executor.builder.body {
+            Some(BodyType::ReqwestBody(body)) => assert_eq!(body.as_bytes(), Some(&[1, 2, 3][..])),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_complete_multipart() {
+        let executor = storage().from().complete_multipart(
+            "thefux",
+            "large.bin",
+            "upload-1",
+            &[
+                S3CompletedPart {
+                    part_number: 1,
+                    etag: "\"a\"".to_string(),
+                },
+                S3CompletedPart {
+                    part_number: 2,
+                    etag: "\"b\"".to_string(),
+                },
+            ],
+        );
+
+        assert_eq!(executor.builder.method, Method::POST);
+        assert_eq!(
+            executor.url().as_str(),
+            "http://localhost/storage/v1/s3/thefux/large.bin?uploadId=upload-1"
+        );
+        assert_eq!(
+            executor
+                .builder
+                .headers
+                .lock()
+                .unwrap()
+                .get("Content-Type")
+                .unwrap(),
+            "application/xml"
+        );
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(
+                val,
+                "<CompleteMultipartUpload>\
+                 <Part><PartNumber>1</PartNumber><ETag>&quot;a&quot;</ETag></Part>\
+                 <Part><PartNumber>2</PartNumber><ETag>&quot;b&quot;</ETag></Part>\
+                 </CompleteMultipartUpload>"
+            ),
+            _ => panic!("nop"),
+        }
+    }
+
+    #[test]
+    fn test_abort_multipart() {
+        let executor = storage()
+            .from()
+            .abort_multipart("thefux", "large.bin", "upload-1");
+
+        assert_eq!(executor.builder.method, Method::DELETE);
+        assert_eq!(
+            executor.url().as_str(),
+            "http://localhost/storage/v1/s3/thefux/large.bin?uploadId=upload-1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_s3_list_objects_pages() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[("content-type", "application/xml")],
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>thefux</Name>
+  <Prefix>invoices/</Prefix>
+  <KeyCount>2</KeyCount>
+  <MaxKeys>2</MaxKeys>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>page-2</NextContinuationToken>
+  <Contents>
+    <Key>invoices/1.pdf</Key>
+    <LastModified>2024-01-08T13:24:17.000Z</LastModified>
+    <ETag>"e5d1b1a0"</ETag>
+    <Size>1024</Size>
+  </Contents>
+  <Contents>
+    <Key>invoices/2.pdf</Key>
+    <Size>2048</Size>
+  </Contents>
+  <CommonPrefixes>
+    <Prefix>invoices/2023/</Prefix>
+  </CommonPrefixes>
+</ListBucketResult>"#,
+        );
+        backend.push_response(
+            200,
+            &[("content-type", "application/xml")],
+            r#"<ListBucketResult>
+  <IsTruncated>false</IsTruncated>
+  <Contents>
+    <Key>invoices/3.pdf</Key>
+    <Size>512</Size>
+  </Contents>
+</ListBucketResult>"#,
+        );
+        let storage = Storage::new("http://localhost/storage/v1").with_backend(backend.clone());
+
+        let first = storage
+            .from()
+            .s3_list_objects("thefux", "invoices/", None)
+            .await
+            .unwrap();
+        assert!(first.is_truncated);
+        assert_eq!(first.contents.len(), 2);
+        assert_eq!(first.contents[0].key, "invoices/1.pdf");
+        assert_eq!(first.contents[0].size, Some(1024));
+        assert_eq!(first.contents[0].etag.as_deref(), Some("\"e5d1b1a0\""));
+        assert_eq!(first.common_prefixes[0].prefix, "invoices/2023/");
+        assert_eq!(first.next_continuation_token.as_deref(), Some("page-2"));
+
+        let second = storage
+            .from()
+            .s3_list_objects(
+                "thefux",
+                "invoices/",
+                first.next_continuation_token.as_deref(),
+            )
+            .await
+            .unwrap();
+        assert!(!second.is_truncated);
+        assert_eq!(second.contents[0].key, "invoices/3.pdf");
+        assert_eq!(second.next_continuation_token, None);
+
+        let requests = backend.requests();
+        assert_eq!(requests[0].url.path(), "/storage/v1/s3/thefux");
+        assert_eq!(
+            requests[0].url.query(),
+            Some("list-type=2&prefix=invoices%2F")
+        );
+        assert_eq!(
+            requests[1].url.query(),
+            Some("list-type=2&prefix=invoices%2F&continuation-token=page-2")
+        );
+    }
+}
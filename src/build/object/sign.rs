@@ -1,8 +1,14 @@
 use reqwest::{header::HeaderValue, Method};
 
-use crate::build::{
-    builder::{BodyType, Builder},
-    executor::Executor,
+use crate::{
+    build::{
+        builder::{object_segments, BodyType, Builder},
+        executor::Executor,
+    },
+    model::{
+        errors,
+        options::{SignedUrlOptions, SignedUrlsOptions},
+    },
 };
 
 impl Builder {
@@ -54,18 +60,69 @@ impl Builder {
             .unwrap()
             .insert("Content-Type", HeaderValue::from_static("application/json"));
         self.method = Method::POST;
+        let bucket_name = self.bucket_id(bucket_name);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push("sign")
             .push(bucket_name)
-            .push(object);
+            .extend(object_segments(object));
 
         self.body = Some(BodyType::StringBody(body.to_string()));
         self.create_executor()
     }
 
+    /// generate presigned url to retrieve an object using a struct
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `object` - object name
+    /// * `options` - The `SignedUrlOptions` struct containing the request body.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::{object::SignedUrl, options::SignedUrlOptions},
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let storage = Storage::new_with_config(config);
+    ///     let options = SignedUrlOptions::expires_in_duration(Duration::from_secs(3600)).unwrap();
+    ///     let url = storage
+    ///         .from()
+    ///         .create_signed_url_from("thefux", "btc.pdf", options)
+    ///         .execute_from::<SignedUrl>()
+    ///         .await
+    ///         .unwrap()
+    ///         .absolute_url(storage.base_url());
+    /// }
+    /// ```
+    pub fn create_signed_url_from(
+        self,
+        bucket_name: &str,
+        object: &str,
+        options: SignedUrlOptions,
+    ) -> Executor {
+        self.create_signed_url(
+            bucket_name,
+            object,
+            &serde_json::to_string(&options).unwrap_or_default(),
+        )
+    }
+
     /// generate presigned urls to retrieve objects
     ///
     /// # Arguments
@@ -104,6 +161,7 @@ impl Builder {
             .unwrap()
             .insert("Content-Type", HeaderValue::from_static("application/json"));
         self.method = Method::POST;
+        let bucket_name = self.bucket_id(bucket_name);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -115,6 +173,114 @@ impl Builder {
         self.create_executor()
     }
 
+    /// generate presigned urls to retrieve objects using a struct
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `options` - The `SignedUrlsOptions` struct containing the request body.
+    ///
+    /// # Returns
+    ///
+    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::{SignedUrlsOptions, Transform, Resize},
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .create_signed_urls_from("thefux", SignedUrlsOptions {
+    ///             expires_in: 3600,
+    ///             paths: vec!["a.png".to_string(), "b.png".to_string()],
+    ///             transform: Some(Transform {
+    ///                 format: None,
+    ///                 height: Some(100),
+    ///                 quality: None,
+    ///                 resize: Some(Resize::Cover),
+    ///                 width: Some(100),
+    ///             }),
+    ///         })
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn create_signed_urls_from(
+        self,
+        bucket_name: &str,
+        options: SignedUrlsOptions,
+    ) -> Executor {
+        self.create_signed_urls(
+            bucket_name,
+            &serde_json::to_string(&options).unwrap_or_default(),
+        )
+    }
+
+    /// generate presigned urls for all objects under a prefix
+    ///
+    /// The storage api can't sign a prefix, the objects currently under the prefix
+    /// (including nested folders) are listed and signed in one batch. Objects added
+    /// afterwards are not covered by the urls.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_name` - bucket name
+    /// * `prefix` - the folder to sign
+    /// * `expires_in` - the number of seconds until the signed urls expire
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Executor, errors::Error>` - The batch signing request, or the error of the listing.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let response = Storage::new_with_config(config)
+    ///         .from()
+    ///         .create_signed_url_for_prefix("thefux", "invoices/2023", 3600)
+    ///         .await
+    ///         .unwrap()
+    ///         .execute()
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn create_signed_url_for_prefix(
+        self,
+        bucket_name: &str,
+        prefix: &str,
+        expires_in: u64,
+    ) -> Result<Executor, errors::Error> {
+        let paths = self.list_prefix_paths(bucket_name, prefix).await?;
+        Ok(self.create_signed_urls_from(
+            bucket_name,
+            SignedUrlsOptions {
+                expires_in,
+                paths,
+                transform: None,
+            },
+        ))
+    }
+
     /// get object via pre-signed url
     ///
     /// # Arguments
@@ -156,15 +322,18 @@ impl Builder {
         object: &str,
         token: &str,
     ) -> Executor {
+        let bucket_name = self.bucket_id(bucket_name);
         self.url
             .path_segments_mut()
             .unwrap()
             .push("object")
             .push("sign")
             .push(bucket_name)
-            .push(object);
+            .extend(object_segments(object));
 
-        self.url.query_pairs_mut().append_pair("token", token);
+        self.url
+            .query_pairs_mut()
+            .append_pair(&self.token_param, token);
 
         self.create_executor()
     }
@@ -176,14 +345,19 @@ mod test {
     use std::sync::{Arc, Mutex};
     use url::{Host, Origin};
 
-    use crate::build::builder::{BodyType, Builder};
+    use crate::{
+        build::builder::{BodyType, Builder},
+        model::options::{Format, SignedUrlOptions, SignedUrlsOptions, Transform},
+        testing::MockBackend,
+        Storage,
+    };
 
     #[test]
     fn test_get_object_with_signed_url() {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .get_object_with_pre_assigned_url("thefux", "btc.pdf", "token");
 
@@ -201,7 +375,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .create_signed_url(
             "thefux",
@@ -251,7 +425,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .create_signed_urls("thefux", r#"{"paths":["btc.pdf","test.pdf"]}"#);
 
@@ -268,4 +442,102 @@ mod test {
         );
         assert_eq!(executor.builder.url.path(), "/object/sign/thefux");
     }
+
+    #[test]
+    fn test_get_object_with_signed_url_custom_token_param() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .token_param("sig")
+        .get_object_with_pre_assigned_url("thefux", "btc.pdf", "token");
+
+        assert_eq!(executor.builder.url.path(), "/object/sign/thefux/btc.pdf");
+        assert_eq!(executor.builder.url.query(), Some("sig=token"));
+    }
+
+    #[test]
+    fn test_create_signed_urls_from_with_transform() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .create_signed_urls_from(
+            "thefux",
+            SignedUrlsOptions {
+                expires_in: 60,
+                paths: vec!["a.png".to_string()],
+                transform: Some(Transform {
+                    format: Some(Format::Webp),
+                    height: None,
+                    quality: Some(80),
+                    resize: None,
+                    width: None,
+                }),
+            },
+        );
+
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(
+                val,
+                r#"{"expiresIn":60,"paths":["a.png"],"transform":{"format":"webp","quality":80}}"#
+            ),
+            _ => panic!("nop"),
+        }
+        assert_eq!(executor.builder.url.path(), "/object/sign/thefux");
+    }
+
+    #[test]
+    fn test_create_signed_url_from_duration() {
+        let executor = Builder::new(
+            url::Url::parse("http://localhost").unwrap(),
+            Arc::new(Mutex::new(HeaderMap::new())),
+            Client::new(),
+        )
+        .create_signed_url_from(
+            "thefux",
+            "btc.pdf",
+            SignedUrlOptions::expires_in_duration(std::time::Duration::from_secs(3600)).unwrap(),
+        );
+
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(val, r#"{"expiresIn":3600}"#),
+            _ => panic!("nop"),
+        }
+        assert_eq!(executor.builder.url.path(), "/object/sign/thefux/btc.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_url_for_prefix() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[],
+            r#"[{"name":"2024","id":null},{"name":"a.pdf","id":"a"}]"#,
+        );
+        backend.push_response(200, &[], r#"[{"name":"b.pdf","id":"b"}]"#);
+
+        let executor = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .create_signed_url_for_prefix("thefux", "/invoices/", 60)
+            .await
+            .unwrap();
+
+        assert_eq!(executor.builder.method, Method::POST);
+        assert_eq!(executor.builder.url.path(), "/object/sign/thefux");
+        match executor.builder.body {
+            Some(BodyType::StringBody(val)) => assert_eq!(
+                val,
+                r#"{"expiresIn":60,"paths":["invoices/a.pdf","invoices/2024/b.pdf"]}"#
+            ),
+            _ => panic!("nop"),
+        }
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url.path(), "/object/list/thefux");
+    }
 }
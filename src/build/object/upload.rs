@@ -1,17 +1,25 @@
+use std::io;
+
 use reqwest::{header::HeaderValue, Body, Method};
 use tokio::fs::File;
 use tokio_util::codec::{BytesCodec, FramedRead};
+use url::Url;
 
 use crate::{
     build::{
-        builder::{BodyType, Builder},
+        builder::{object_segments, BodyType, Builder},
         executor::Executor,
     },
-    model::options::FileOptions,
+    model::{
+        errors,
+        object::{SingedUrlToUpload, UploadResult},
+        options::{cache_control_value, FileOptions},
+    },
 };
 
 impl Builder {
     fn url(&mut self, bucket_id: &str, object: &str) {
+        let bucket_id = self.bucket_id(bucket_id);
         self.url
             .path_segments_mut()
             .unwrap()
@@ -19,7 +27,7 @@ impl Builder {
             .push("upload")
             .push("sign")
             .push(bucket_id)
-            .push(object);
+            .extend(object_segments(object));
     }
 
     /// generate pre-signed url to upload an object
@@ -72,7 +80,8 @@ impl Builder {
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
     ///
     /// # Example
     /// ```
@@ -94,8 +103,10 @@ impl Builder {
     ///             cache_control: None,
     ///             content_type: Some("application/pdf".to_string()),
     ///             upsert: Some(true),
+    ///             content_disposition: None,
     ///         })
     ///         .await
+    ///         .unwrap()
     ///         .execute()
     ///         .await
     ///         .unwrap();
@@ -108,31 +119,113 @@ impl Builder {
         token: &str,
         file_path: &str,
         file_options: FileOptions,
-    ) -> Executor {
+    ) -> Result<Executor, io::Error> {
         self.method = Method::PUT;
         self.url(bucket_id, object);
 
         if let Some(cache_content) = file_options.cache_control {
             self.headers.lock().unwrap().insert(
                 "cache-control",
-                HeaderValue::from_str(&format!("max-age={}", cache_content)).unwrap(),
+                HeaderValue::from_str(&cache_control_value(cache_content)).unwrap(),
             );
         }
 
-        if let Some(content_type) = file_options.content_type {
+        if let Some(content_type) = file_options.content_type.filter(|value| !value.is_empty()) {
             self.headers.lock().unwrap().insert(
                 "content-type",
                 HeaderValue::from_str(&content_type).unwrap(),
             );
         }
 
-        self.url.query_pairs_mut().append_pair("token", token);
+        if let Some(content_disposition) = file_options.content_disposition {
+            self.content_disposition(&content_disposition);
+        }
+
+        self.url
+            .query_pairs_mut()
+            .append_pair(&self.token_param, token);
 
-        let file = File::open(file_path).await.unwrap();
+        let file = File::open(file_path).await?;
         let stream = FramedRead::new(file, BytesCodec::new());
         self.body = Some(BodyType::ReqwestBody(Body::wrap_stream(stream)));
 
-        self.create_executor()
+        Ok(self.create_executor())
+    }
+
+    /// create a pre-signed upload url and upload the file to it
+    ///
+    /// Combines `create_signed_upload_url` and `upload_to_signed_url_async`,
+    /// the token is extracted from the signed url.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - bucket id
+    /// * `object` - object name
+    /// * `file_path` - file path
+    /// * `file_options` - file options
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UploadResult, errors::Error>` - The result of the upload.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{
+    ///     Storage,
+    ///     config::SupabaseConfig,
+    ///     model::options::FileOptions,
+    /// };
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let result = Storage::new_with_config(config)
+    ///         .from()
+    ///         .signed_upload("thefux", "btc.pdf", "out/test.pdf", FileOptions {
+    ///             cache_control: None,
+    ///             content_type: Some("application/pdf".to_string()),
+    ///             upsert: Some(true),
+    ///             content_disposition: None,
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub async fn signed_upload(
+        self,
+        bucket_id: &str,
+        object: &str,
+        file_path: &str,
+        file_options: FileOptions,
+    ) -> Result<UploadResult, errors::Error> {
+        let upload = self.fork();
+
+        let signed = self
+            .create_signed_upload_url(bucket_id, object)
+            .execute_from::<SingedUrlToUpload>()
+            .await?;
+
+        let token = Url::parse("http://localhost")
+            .and_then(|base| base.join(&signed.url))
+            .ok()
+            .and_then(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "token")
+                    .map(|(_, token)| token.into_owned())
+            })
+            .ok_or_else(|| errors::Error {
+                status_code: "200".to_string(),
+                error: "InvalidResponse".to_string(),
+                message: format!("no token found in the signed url {}", signed.url),
+                context: None,
+            })?;
+
+        upload
+            .upload_to_signed_url_async(bucket_id, object, &token, file_path, file_options)
+            .await?
+            .execute_from::<UploadResult>()
+            .await
     }
 
     /// upload object via pre-signed url with auto detecting content-type
@@ -146,7 +239,8 @@ impl Builder {
     ///
     /// # Returns
     ///
-    /// * `Executor` - The constructed `Executor` instance for executing the request.
+    /// * `Result<Executor, io::Error>` - The constructed `Executor` instance for executing the request,
+    ///   or the error opening the file.
     ///
     /// # Example
     /// ```
@@ -165,6 +259,7 @@ impl Builder {
     ///         .from()
     ///         .upload_to_signed_url_no_options_async("thefux", "btc.pdf", "<token>", "out/test.pdf")
     ///         .await
+    ///         .unwrap()
     ///         .execute()
     ///         .await
     ///         .unwrap();
@@ -176,7 +271,7 @@ impl Builder {
         object: &str,
         token: &str,
         file_path: &str,
-    ) -> Executor {
+    ) -> Result<Executor, io::Error> {
         let mime = mime_guess::from_path(object)
             .first_or_octet_stream()
             .to_string();
@@ -188,13 +283,15 @@ impl Builder {
         self.method = Method::PUT;
         self.url(bucket_id, object);
 
-        self.url.query_pairs_mut().append_pair("token", token);
+        self.url
+            .query_pairs_mut()
+            .append_pair(&self.token_param, token);
 
-        let file = File::open(file_path).await.unwrap();
+        let file = File::open(file_path).await?;
         let stream = FramedRead::new(file, BytesCodec::new());
         self.body = Some(BodyType::ReqwestBody(Body::wrap_stream(stream)));
 
-        self.create_executor()
+        Ok(self.create_executor())
     }
 
     /// upload object via pre-signed url
@@ -233,6 +330,7 @@ impl Builder {
     ///                 cache_control: None,
     ///                 content_type: None,
     ///                 upsert: None,
+    ///                 content_disposition: None,
     ///             })
     ///         .execute()
     ///         .await
@@ -250,15 +348,17 @@ impl Builder {
         if let Some(cache_content) = file_options.cache_control {
             self.headers.lock().unwrap().insert(
                 "cache-control",
-                HeaderValue::from_str(&format!("max-age={}", cache_content)).unwrap(),
+                HeaderValue::from_str(&cache_control_value(cache_content)).unwrap(),
             );
         }
 
         if let Some(content_type) = file_options.content_type {
-            self.headers.lock().unwrap().insert(
-                "content-type",
-                HeaderValue::from_str(&content_type).unwrap(),
-            );
+            if !content_type.is_empty() {
+                self.headers.lock().unwrap().insert(
+                    "content-type",
+                    HeaderValue::from_str(&content_type).unwrap(),
+                );
+            }
         } else {
             let mime = mime_guess::from_path(object)
                 .first_or_octet_stream()
@@ -269,6 +369,10 @@ impl Builder {
                 .insert("Content-Type", HeaderValue::from_str(&mime).unwrap());
         }
 
+        if let Some(content_disposition) = file_options.content_disposition {
+            self.content_disposition(&content_disposition);
+        }
+
         if let Some(upsert) = file_options.upsert {
             self.headers.lock().unwrap().insert(
                 "x-upsert",
@@ -279,7 +383,9 @@ impl Builder {
         self.method = Method::PUT;
         self.url(bucket_id, object);
 
-        self.url.query_pairs_mut().append_pair("token", token);
+        self.url
+            .query_pairs_mut()
+            .append_pair(&self.token_param, token);
 
         let stream = FramedRead::new(file, BytesCodec::new());
         self.body = Some(BodyType::ReqwestBody(Body::wrap_stream(stream)));
@@ -295,13 +401,14 @@ mod test {
     use url::{Host, Origin};
 
     use super::*;
+    use crate::{testing::MockBackend, Storage};
 
     #[test]
     fn test_create_signed_upload_url() {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .create_signed_upload_url("thefux", "bitcoin.pdf");
 
@@ -321,7 +428,7 @@ mod test {
         let executor = Builder::new(
             url::Url::parse("http://localhost").unwrap(),
             Arc::new(Mutex::new(HeaderMap::new())),
-            Arc::new(Mutex::new(Client::new())),
+            Client::new(),
         )
         .upload_to_signed_url_async(
             "thefux",
@@ -332,9 +439,11 @@ mod test {
                 cache_control: None,
                 content_type: Some("application/pdf".to_string()),
                 upsert: Some(true),
+                content_disposition: None,
             },
         )
-        .await;
+        .await
+        .unwrap();
 
         assert_eq!(executor.builder.method, Method::PUT);
         assert_eq!(
@@ -347,4 +456,42 @@ mod test {
         );
         assert_eq!(executor.builder.url.query(), Some("token=token"));
     }
+
+    #[tokio::test]
+    async fn test_signed_upload() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[],
+            r#"{"url":"/object/upload/sign/thefux/btc.pdf?token=abc"}"#,
+        );
+        backend.push_response(200, &[], r#"{"Key":"thefux/btc.pdf"}"#);
+
+        let result = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .signed_upload(
+                "thefux",
+                "btc.pdf",
+                "out/test.pdf",
+                FileOptions {
+                    cache_control: None,
+                    content_type: Some("application/pdf".to_string()),
+                    upsert: Some(true),
+                    content_disposition: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.key, "thefux/btc.pdf");
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].url.path(), "/object/upload/sign/thefux/btc.pdf");
+        assert_eq!(requests[1].method, Method::PUT);
+        assert_eq!(requests[1].url.path(), "/object/upload/sign/thefux/btc.pdf");
+        assert_eq!(requests[1].url.query(), Some("token=abc"));
+    }
 }
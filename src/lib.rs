@@ -1,23 +1,44 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    Client,
+    header::{HeaderMap, HeaderValue, DATE},
+    Client, Method,
 };
-use url::Url;
+use url::{Host, Url};
 
+pub mod bucket_handle;
 pub mod build;
 pub mod config;
 pub mod model;
+pub mod prelude;
+pub mod storage_builder;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod util;
 
-use build::builder::Builder;
+pub use bucket_handle::BucketHandle;
+use build::{
+    backend::HttpBackend,
+    builder::{Builder, TokenProvider},
+};
 use config::SupabaseConfig;
+use model::{errors, options::FileOptions};
+use storage_builder::ClientConfig;
+pub use storage_builder::StorageBuilder;
 
 /// A struct representing a Storage with an associated client and headers.
 pub struct Storage {
     url: url::Url,
     headers: HeaderMap,
     client: Client,
+    client_config: ClientConfig,
+    backend: Option<Arc<dyn HttpBackend>>,
+    compress_body: bool,
+    default_file_options: Option<FileOptions>,
+    token_provider: Option<TokenProvider>,
 }
 
 impl Storage {
@@ -42,9 +63,86 @@ impl Storage {
             url: Url::parse(&url.into()).unwrap(),
             headers: HeaderMap::new(),
             client: Client::new(),
+            client_config: ClientConfig::default(),
+            backend: None,
+            compress_body: false,
+            default_file_options: None,
+            token_provider: None,
         }
     }
 
+    /// Creates a new `Storage` instance for the local supabase stack,
+    /// `http://localhost:{port}/storage/v1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port of the local api gateway, `54321` by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new_local(54321);
+    /// assert_eq!(storage.base_url().as_str(), "http://localhost:54321/storage/v1");
+    /// ```
+    pub fn new_local(port: u16) -> Self {
+        Self::new(format!("http://localhost:{}/storage/v1", port))
+    }
+
+    /// Whether an api key would be sent over plain http to a host other than the local machine.
+    ///
+    /// Useful to guard against sending the service key unencrypted in production.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    ///
+    /// let storage = Storage::new_with_config(SupabaseConfig {
+    ///     supabase_url_storage: "http://storage.example.com/storage/v1".to_string(),
+    ///     supabase_api_key: Some("your-api-key".to_string()),
+    /// });
+    /// assert!(storage.sends_key_over_plaintext());
+    /// ```
+    pub fn sends_key_over_plaintext(&self) -> bool {
+        let has_key = self.headers.contains_key("Authorization")
+            || self.headers.contains_key("apiKey")
+            || self.token_provider.is_some();
+        let is_loopback = match self.url.host() {
+            Some(Host::Domain(domain)) => domain == "localhost",
+            Some(Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        };
+
+        has_key && self.url.scheme() == "http" && !is_loopback
+    }
+
+    /// Creates a new `StorageBuilder` to configure the client options, base url and auth at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The base URL for the storage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("https://your_project_path/storage/v1")
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder<T>(url: T) -> StorageBuilder
+    where
+        T: Into<String>,
+    {
+        StorageBuilder::new(url)
+    }
+
     /// Creates a new `Storage` instance with provided configuration.
     ///
     /// # Arguments
@@ -61,24 +159,277 @@ impl Storage {
     /// let storage = Storage::new_with_config(config);
     /// ```
     pub fn new_with_config(config: SupabaseConfig) -> Self {
+        Self::try_new_with_config(config).expect("config is invalid")
+    }
+
+    /// Creates a new `Storage` instance with provided configuration,
+    /// returning an error instead of panicking if the configuration is invalid.
+    ///
+    /// Whitespace around the api key, e.g. a trailing newline, is trimmed.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The `SupabaseConfig` containing the necessary configuration for Supabase.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    ///
+    /// let storage = Storage::try_new_with_config(SupabaseConfig {
+    ///     supabase_url_storage: "http://localhost/storage/v1".to_string(),
+    ///     supabase_api_key: Some("your-api-key\n".to_string()),
+    /// });
+    /// assert!(storage.is_ok());
+    /// ```
+    pub fn try_new_with_config(config: SupabaseConfig) -> Result<Self, errors::Error> {
+        let invalid_config = |message: String| errors::Error {
+            status_code: String::new(),
+            error: "InvalidConfig".to_string(),
+            message,
+            context: None,
+        };
+
         let mut headers = HeaderMap::new();
         if let Some(api_key) = config.supabase_api_key {
+            let api_key = api_key.trim();
+            let invalid_key = |_| invalid_config("the api key is not a valid header value".into());
             headers.insert(
                 "Authorization",
-                HeaderValue::from_str(&format!("Bearer {}", api_key))
-                    .expect("header value is invalid"),
+                HeaderValue::from_str(&format!("Bearer {}", api_key)).map_err(invalid_key)?,
             );
             headers.insert(
                 "apiKey",
-                HeaderValue::from_str(&api_key).expect("header value is invalid"),
+                HeaderValue::from_str(api_key).map_err(invalid_key)?,
             );
         }
 
-        Self {
-            url: Url::parse(&config.supabase_url_storage).unwrap(),
+        let url = Url::parse(&config.supabase_url_storage)
+            .map_err(|error| invalid_config(format!("the storage url is invalid: {}", error)))?;
+
+        Ok(Self {
+            url,
             headers,
             client: Client::new(),
-        }
+            client_config: ClientConfig::default(),
+            backend: None,
+            compress_body: false,
+            default_file_options: None,
+            token_provider: None,
+        })
+    }
+
+    /// Creates a new `Storage` instance from a supabase project ref and an api key.
+    ///
+    /// The storage url is `https://{project_ref}.supabase.co/storage/v1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `project_ref` - The reference id of the supabase project.
+    /// * `api_key` - The api key used to authorize the requests.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::from_project_ref("abcd1234", "your-api-key");
+    /// ```
+    pub fn from_project_ref(project_ref: &str, api_key: &str) -> Self {
+        Self::new_with_config(SupabaseConfig {
+            supabase_url_storage: format!("https://{}.supabase.co/storage/v1", project_ref),
+            supabase_api_key: Some(api_key.to_string()),
+        })
+    }
+
+    /// Enables transparent decompression of gzip and brotli encoded responses.
+    ///
+    /// Decompression is already enabled by default, the client is rebuilt keeping the options
+    /// set before, e.g. by `with_pool_config` or the `StorageBuilder`.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1").with_compression();
+    /// ```
+    pub fn with_compression(mut self) -> Self {
+        self.client_config.compression = Some(true);
+        self.client = self
+            .client_config
+            .build()
+            .expect("failed to build the client");
+        self
+    }
+
+    /// Tunes the connection pool of the client.
+    ///
+    /// The defaults of reqwest keep an unlimited number of idle connections per host,
+    /// closed after 90 seconds of inactivity.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_idle_per_host` - The maximum number of idle connections kept per host.
+    /// * `pool_idle_timeout` - How long idle connections are kept, `None` keeps them forever.
+    ///
+    /// The client is rebuilt keeping the options set before, e.g. a timeout set with the
+    /// `StorageBuilder`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1")
+    ///     .with_pool_config(32, Some(Duration::from_secs(30)));
+    /// ```
+    pub fn with_pool_config(
+        mut self,
+        max_idle_per_host: usize,
+        pool_idle_timeout: Option<Duration>,
+    ) -> Self {
+        self.client_config.pool_max_idle_per_host = Some(max_idle_per_host);
+        self.client_config.pool_idle_timeout = Some(pool_idle_timeout);
+        self.client = self
+            .client_config
+            .build()
+            .expect("failed to build the client");
+        self
+    }
+
+    /// Sends all requests through the given `HttpBackend` instead of the internal client.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1")
+    ///     .with_backend(Arc::new(reqwest::Client::new()));
+    /// ```
+    pub fn with_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Sets the `FileOptions` used by uploads when no options are passed explicitly.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, model::options::FileOptions};
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1")
+    ///     .with_default_file_options(FileOptions {
+    ///         cache_control: Some(3600),
+    ///         content_type: None,
+    ///         upsert: Some(true),
+    ///         content_disposition: None,
+    ///     });
+    /// ```
+    pub fn with_default_file_options(mut self, file_options: FileOptions) -> Self {
+        self.default_file_options = Some(file_options);
+        self
+    }
+
+    /// Fetches the bearer token from the given provider for every request,
+    /// overriding the `Authorization` header set at construction.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1")
+    ///     .with_token_provider(Arc::new(|| "fresh-token".to_string()));
+    /// ```
+    pub fn with_token_provider(mut self, token_provider: TokenProvider) -> Self {
+        self.token_provider = Some(token_provider);
+        self
+    }
+
+    /// Adds headers sent with every request, e.g. `X-Forwarded-*` headers when running behind a proxy.
+    ///
+    /// Headers with the same name as an existing header replace it.
+    ///
+    /// # Example
+    /// ```
+    /// use reqwest::header::{HeaderMap, HeaderValue};
+    /// use supabase_storage::Storage;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("x-forwarded-host", HeaderValue::from_static("storage.example.com"));
+    /// let storage = Storage::new("https://your_project_path/storage/v1").with_default_headers(headers);
+    /// ```
+    pub fn with_default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Gzip string request bodies (e.g. json bodies) and set the `Content-Encoding: gzip` header.
+    ///
+    /// Streamed bodies, such as file uploads, are not compressed.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1").with_body_compression();
+    /// ```
+    pub fn with_body_compression(mut self) -> Self {
+        self.compress_body = true;
+        self
+    }
+
+    /// The base url of the storage.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1");
+    /// assert_eq!(storage.base_url().as_str(), "https://your_project_path/storage/v1");
+    /// ```
+    pub fn base_url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Reads the time of the server from the `Date` header of a `HEAD /bucket` request,
+    /// e.g. to diagnose signed urls which are already expired because of a drifting local clock.
+    ///
+    /// The status of the response is ignored, error responses carry the `Date` header as well.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, errors::Error>` - The `Date` header, e.g. `Mon, 08 Jan 2024 13:24:17 GMT`.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    /// use dotenv::dotenv;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     dotenv().ok();
+    ///     let config = SupabaseConfig::default();
+    ///     let date = Storage::new_with_config(config).server_time().await;
+    /// }
+    /// ```
+    pub async fn server_time(&self) -> Result<String, errors::Error> {
+        let response = self
+            .from()
+            .raw_request(Method::HEAD, &["bucket"])
+            .execute()
+            .await?;
+
+        response
+            .headers()
+            .get(DATE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+            .ok_or_else(|| errors::Error {
+                status_code: response.status().as_str().to_string(),
+                error: "InvalidResponse".to_string(),
+                message: "the response has no Date header".to_string(),
+                context: Some("HEAD /bucket".to_string()),
+            })
     }
 
     /// Creates a new `Builder` instance to build an action
@@ -91,10 +442,474 @@ impl Storage {
     /// let builder = storage.from();
     /// ```
     pub fn from(&self) -> Builder {
-        Builder::new(
+        let mut builder = self.builder_with_headers(self.headers.clone());
+        builder.token_provider = self.token_provider.clone();
+        builder
+    }
+
+    /// Creates a `BucketHandle` to work within a single bucket without repeating its id.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_id` - The id of the bucket.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::new("https://your_project_path/storage/v1");
+    /// let executor = storage.bucket("thefux").download("btc.pdf");
+    /// ```
+    pub fn bucket(&self, bucket_id: &str) -> BucketHandle<'_> {
+        BucketHandle::new(self, bucket_id)
+    }
+
+    /// Creates a new `Builder` instance sending only the given headers,
+    /// e.g. to fetch a public object without the api key.
+    ///
+    /// Neither the headers of the `Storage` nor its token provider are used.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the request.
+    ///
+    /// # Example
+    /// ```
+    /// use reqwest::header::HeaderMap;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::from_project_ref("abcd1234", "your-api-key");
+    /// let executor = storage
+    ///     .from_with_headers(HeaderMap::new())
+    ///     .get_public_object("thefux", "btc.pdf");
+    /// ```
+    pub fn from_with_headers(&self, headers: HeaderMap) -> Builder {
+        self.builder_with_headers(headers)
+    }
+
+    fn builder_with_headers(&self, headers: HeaderMap) -> Builder {
+        let mut builder = Builder::new(
             self.url.clone(),
-            Arc::new(Mutex::new(self.headers.clone())),
-            Arc::new(Mutex::new(self.client.clone())),
-        )
+            Arc::new(Mutex::new(headers)),
+            self.client.clone(),
+        );
+        builder.backend = self.backend.clone();
+        builder.compress_body = self.compress_body;
+        builder.default_file_options = self.default_file_options.clone();
+        builder
+    }
+}
+
+impl From<SupabaseConfig> for Storage {
+    /// Same as `Storage::new_with_config`, panics if the configuration is invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::{Storage, config::SupabaseConfig};
+    ///
+    /// let storage: Storage = SupabaseConfig {
+    ///     supabase_url_storage: "http://localhost/storage/v1".to_string(),
+    ///     supabase_api_key: Some("your-api-key".to_string()),
+    /// }
+    /// .into();
+    /// ```
+    fn from(config: SupabaseConfig) -> Self {
+        Self::new_with_config(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Write,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use flate2::{write::GzEncoder, Compression};
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        Request,
+    };
+
+    use super::Storage;
+    use crate::{build::backend::HttpBackend, config::SupabaseConfig, testing::MockBackend};
+
+    /// answers after a delay, recording the highest number of requests in flight
+    #[derive(Default)]
+    struct ConcurrentBackend {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HttpBackend for ConcurrentBackend {
+        async fn send(&self, _request: Request) -> Result<reqwest::Response, reqwest::Error> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(http::Response::new("[]").into())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shared_storage_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Storage>();
+
+        let backend = Arc::new(ConcurrentBackend::default());
+        let storage = Arc::new(Storage::new("http://localhost").with_backend(backend.clone()));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let storage = storage.clone();
+                tokio::spawn(async move { storage.from().get_buckets().execute().await })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().unwrap().status(), 200);
+        }
+
+        assert!(backend.max_in_flight.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_try_new_with_config_trims_api_key() {
+        let storage = Storage::try_new_with_config(SupabaseConfig {
+            supabase_url_storage: "http://localhost".to_string(),
+            supabase_api_key: Some(" key\n".to_string()),
+        })
+        .unwrap();
+        assert_eq!(storage.headers.get("Authorization").unwrap(), "Bearer key");
+        assert_eq!(storage.headers.get("apiKey").unwrap(), "key");
+
+        let result = Storage::try_new_with_config(SupabaseConfig {
+            supabase_url_storage: "http://localhost".to_string(),
+            supabase_api_key: Some("k\ney".to_string()),
+        });
+        assert_eq!(result.err().unwrap().error, "InvalidConfig");
+    }
+
+    #[test]
+    fn test_from_config() {
+        let storage: Storage = SupabaseConfig {
+            supabase_url_storage: "http://localhost:54321/storage/v1".to_string(),
+            supabase_api_key: Some("key".to_string()),
+        }
+        .into();
+
+        assert_eq!(
+            storage.base_url().as_str(),
+            "http://localhost:54321/storage/v1"
+        );
+        assert_eq!(storage.headers.get("Authorization").unwrap(), "Bearer key");
+        assert_eq!(storage.headers.get("apiKey").unwrap(), "key");
+    }
+
+    #[test]
+    fn test_base_url() {
+        let storage = Storage::new("http://localhost:54321/storage/v1");
+        assert_eq!(
+            storage.base_url().as_str(),
+            "http://localhost:54321/storage/v1"
+        );
+    }
+
+    #[test]
+    fn test_from_project_ref() {
+        let storage = Storage::from_project_ref("abcd1234", "key");
+
+        assert_eq!(
+            storage.url.as_str(),
+            "https://abcd1234.supabase.co/storage/v1"
+        );
+        assert_eq!(storage.headers.get("Authorization").unwrap(), "Bearer key");
+        assert_eq!(storage.headers.get("apiKey").unwrap(), "key");
+    }
+
+    #[tokio::test]
+    async fn test_with_pool_config() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/bucket")
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let storage = Storage::new(server.url()).with_pool_config(1, Some(Duration::from_secs(1)));
+        for _ in 0..2 {
+            let response = storage.from().get_buckets().execute().await.unwrap();
+            assert_eq!(response.status(), 200);
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_with_pool_config_keeps_client_options() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/bucket")
+            .match_header("user-agent", "my-app/1.0")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let storage = Storage::builder(server.url())
+            .user_agent("my-app/1.0")
+            .build()
+            .unwrap()
+            .with_pool_config(1, Some(Duration::from_secs(1)));
+        storage.from().get_buckets().execute().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_decode_gzip_response() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello supabase").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/object/thefux/hello.txt")
+            .with_header("content-encoding", "gzip")
+            .with_body(encoder.finish().unwrap())
+            .create_async()
+            .await;
+
+        let text = Storage::new(server.url())
+            .with_compression()
+            .from()
+            .get_object("thefux", "hello.txt")
+            .execute()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(text, "hello supabase");
+    }
+
+    #[tokio::test]
+    async fn test_with_compression_keeps_client_options() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/bucket")
+            .match_header("user-agent", "my-app/1.0")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let storage = Storage::builder(server.url())
+            .user_agent("my-app/1.0")
+            .build()
+            .unwrap()
+            .with_compression();
+        storage.from().get_buckets().execute().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_provider() {
+        let backend = Arc::new(MockBackend::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let storage = Storage::from_project_ref("abcd1234", "static")
+            .with_backend(backend.clone())
+            .with_token_provider(Arc::new(move || {
+                format!("token-{}", counter.fetch_add(1, Ordering::SeqCst) + 1)
+            }));
+
+        for _ in 0..2 {
+            storage
+                .from()
+                .get_bucket_details("thefux")
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        let requests = backend.requests();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            requests[0].headers.get("Authorization").unwrap(),
+            "Bearer token-1"
+        );
+        assert_eq!(
+            requests[1].headers.get("Authorization").unwrap(),
+            "Bearer token-2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_provider_invalid_token() {
+        let backend = Arc::new(MockBackend::new());
+        let storage = |token: &'static str| {
+            Storage::new("http://localhost")
+                .with_backend(backend.clone())
+                .with_token_provider(Arc::new(move || token.to_string()))
+        };
+
+        storage("tok\n")
+            .from()
+            .get_bucket_details("thefux")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.requests()[0].headers.get("Authorization").unwrap(),
+            "Bearer tok"
+        );
+
+        let error = storage("bad\u{7f}")
+            .from()
+            .get_bucket_details("thefux")
+            .execute()
+            .await
+            .unwrap_err();
+        assert_eq!(error.error, "InvalidHeader");
+        assert!(!error.message.contains("bad"));
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_time() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[("date", "Mon, 08 Jan 2024 13:24:17 GMT")], "");
+        backend.push_response(200, &[], "");
+        let storage = Storage::new("http://localhost/storage/v1").with_backend(backend.clone());
+
+        assert_eq!(
+            storage.server_time().await.unwrap(),
+            "Mon, 08 Jan 2024 13:24:17 GMT"
+        );
+        let request = &backend.requests()[0];
+        assert_eq!(request.method, reqwest::Method::HEAD);
+        assert_eq!(request.url.path(), "/storage/v1/bucket");
+
+        assert_eq!(
+            storage.server_time().await.unwrap_err().error,
+            "InvalidResponse"
+        );
+    }
+
+    #[test]
+    fn test_new_local() {
+        assert_eq!(
+            Storage::new_local(54321).base_url().as_str(),
+            "http://localhost:54321/storage/v1"
+        );
+        assert_eq!(
+            Storage::new_local(8000).base_url().as_str(),
+            "http://localhost:8000/storage/v1"
+        );
+        assert!(!Storage::new_local(54321).sends_key_over_plaintext());
+    }
+
+    #[test]
+    fn test_sends_key_over_plaintext() {
+        let storage = |url: &str| {
+            Storage::new_with_config(SupabaseConfig {
+                supabase_url_storage: url.to_string(),
+                supabase_api_key: Some("key".to_string()),
+            })
+        };
+
+        assert!(storage("http://storage.example.com").sends_key_over_plaintext());
+        assert!(!storage("https://storage.example.com").sends_key_over_plaintext());
+        assert!(!storage("http://localhost:54321").sends_key_over_plaintext());
+        assert!(!storage("http://127.0.0.1:54321").sends_key_over_plaintext());
+        assert!(!storage("http://[::1]:54321").sends_key_over_plaintext());
+        assert!(!Storage::new("http://storage.example.com").sends_key_over_plaintext());
+    }
+
+    #[test]
+    fn test_https_only_storage_rejects_http() {
+        let storage = Storage::builder("https://abcd1234.supabase.co/storage/v1")
+            .https_only()
+            .build()
+            .unwrap();
+        assert_eq!(storage.base_url().scheme(), "https");
+
+        let error = Storage::builder("http://storage.example.com/storage/v1")
+            .api_key("key")
+            .https_only()
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(error.error, "InvalidConfig");
+        assert_eq!(
+            error.message,
+            "the storage url http://storage.example.com/storage/v1 is not https"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_with_headers() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], "pdf");
+        let storage = Storage::from_project_ref("abcd1234", "key")
+            .with_backend(backend.clone())
+            .with_token_provider(Arc::new(|| "token".to_string()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-client", HeaderValue::from_static("public"));
+        let builder = storage.from_with_headers(headers);
+        assert!(builder
+            .headers
+            .lock()
+            .unwrap()
+            .get("Authorization")
+            .is_none());
+
+        builder
+            .get_public_object("thefux", "btc.pdf")
+            .execute()
+            .await
+            .unwrap();
+
+        let request = &backend.requests()[0];
+        assert!(request.headers.get("Authorization").is_none());
+        assert!(request.headers.get("apiKey").is_none());
+        assert_eq!(request.headers.get("x-client").unwrap(), "public");
+    }
+
+    #[tokio::test]
+    async fn test_default_headers() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(200, &[], "pdf");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-host",
+            HeaderValue::from_static("storage.example.com"),
+        );
+        let storage = Storage::from_project_ref("abcd1234", "key")
+            .with_backend(backend.clone())
+            .with_default_headers(headers);
+
+        storage
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute()
+            .await
+            .unwrap();
+
+        let request = &backend.requests()[0];
+        assert_eq!(
+            request.headers.get("x-forwarded-host").unwrap(),
+            "storage.example.com"
+        );
+        assert_eq!(request.headers.get("Authorization").unwrap(), "Bearer key");
     }
 }
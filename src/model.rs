@@ -2,3 +2,4 @@ pub mod bucket;
 pub mod errors;
 pub mod object;
 pub mod options;
+pub mod request;
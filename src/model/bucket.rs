@@ -1,11 +1,67 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize)]
+use super::errors::{self, BucketNameError};
+
+/// maximum number of characters of a bucket name
+pub const BUCKET_NAME_MAX_LENGTH: usize = 100;
+
+/// Checks a bucket name against the naming rules of the storage api:
+/// lowercase letters, digits, `.`, `-` and `_`, at most `BUCKET_NAME_MAX_LENGTH` characters.
+///
+/// # Example
+/// ```
+/// use supabase_storage::model::{bucket::validate_bucket_name, errors::BucketNameError};
+///
+/// assert!(validate_bucket_name("thefux").is_ok());
+/// assert_eq!(validate_bucket_name("The Fux"), Err(BucketNameError::InvalidCharacter('T')));
+/// ```
+pub fn validate_bucket_name(name: &str) -> Result<(), BucketNameError> {
+    if name.is_empty() {
+        return Err(BucketNameError::Empty);
+    }
+
+    let length = name.chars().count();
+    if length > BUCKET_NAME_MAX_LENGTH {
+        return Err(BucketNameError::TooLong {
+            length,
+            max: BUCKET_NAME_MAX_LENGTH,
+        });
+    }
+
+    match name
+        .chars()
+        .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_')))
+    {
+        Some(c) => Err(BucketNameError::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+/// Normalizes a bucket id before it's put into a request.
+///
+/// Surrounding whitespace is trimmed, the rest must follow the same rules as new buckets,
+/// see `validate_bucket_name`.
+///
+/// # Example
+/// ```
+/// use supabase_storage::model::{bucket::normalize_bucket_id, errors::BucketNameError};
+///
+/// assert_eq!(normalize_bucket_id(" thefux "), Ok("thefux"));
+/// assert_eq!(normalize_bucket_id("the/fux"), Err(BucketNameError::InvalidCharacter('/')));
+/// ```
+pub fn normalize_bucket_id(id: &str) -> Result<&str, BucketNameError> {
+    let id = id.trim();
+    validate_bucket_name(id)?;
+    Ok(id)
+}
+
+/// * file_size_limit: The maximum size of an object in bytes, larger than 4 GiB on paid plans.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct NewBucket {
     pub name: String,
     pub id: Option<String>,
     pub public: Option<bool>,
-    pub file_size_limit: Option<u32>,
+    pub file_size_limit: Option<u64>,
     pub allowed_mime_types: Option<Vec<String>>,
 }
 
@@ -19,6 +75,17 @@ impl NewBucket {
             allowed_mime_types: None,
         }
     }
+
+    /// Checks the name and id of the bucket, see `validate_bucket_name`.
+    ///
+    /// The error is an `InvalidBucketName` `errors::Error`, the same type as `BucketUpdate::validate`.
+    pub fn validate(&self) -> Result<(), errors::Error> {
+        validate_bucket_name(&self.name)?;
+        if let Some(id) = &self.id {
+            validate_bucket_name(id)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -26,16 +93,139 @@ pub struct BucketDetails {
     pub name: String,
     pub id: String,
     pub public: bool,
-    pub file_size_limit: Option<u32>,
+    pub file_size_limit: Option<u64>,
     pub allowed_mime_types: Option<Vec<String>>,
     pub owner: Option<String>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct BucketUpdate {
-    pub public: bool,
-    pub file_size_limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size_limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_mime_types: Option<Vec<String>>,
 }
+
+impl BucketUpdate {
+    /// Checks the allowed mime types, each must be a syntactically valid `type/subtype`
+    /// like `application/vnd.acme+json` or a wildcard like `image/*`, e.g. `pdf` is rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::model::bucket::BucketUpdate;
+    ///
+    /// let update = BucketUpdate {
+    ///     allowed_mime_types: Some(vec!["image/*".to_string(), "application/pdf".to_string()]),
+    ///     ..Default::default()
+    /// };
+    /// assert!(update.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), errors::Error> {
+        let invalid = self
+            .allowed_mime_types
+            .iter()
+            .flatten()
+            .find(|mime_type| !is_valid_mime_type(mime_type));
+
+        match invalid {
+            Some(mime_type) => Err(errors::Error {
+                status_code: "400".to_string(),
+                error: "InvalidMimeType".to_string(),
+                message: format!("{} is not a valid mime type", mime_type),
+                context: None,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A `type/subtype` pair of RFC 7230 tokens, which includes wildcards like `image/*`.
+fn is_valid_mime_type(mime_type: &str) -> bool {
+    let is_token = |value: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+    };
+
+    match mime_type.split_once('/') {
+        Some((type_, subtype)) => is_token(type_) && is_token(subtype),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_bucket_update() {
+        let update = |mime_type: &str| BucketUpdate {
+            allowed_mime_types: Some(vec![mime_type.to_string()]),
+            ..Default::default()
+        };
+
+        assert!(update("application/pdf").validate().is_ok());
+        assert!(update("image/*").validate().is_ok());
+        assert!(update("application/vnd.acme+json").validate().is_ok());
+        assert!(update("application/x-ndjson").validate().is_ok());
+        assert!(update("pdf").validate().is_err());
+        assert!(update("application/").validate().is_err());
+        assert!(update("application/pdf json").validate().is_err());
+        assert!(BucketUpdate::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_serialize_large_file_size_limit() {
+        let bucket = NewBucket {
+            file_size_limit: Some(500 * 1024 * 1024 * 1024),
+            ..NewBucket::new("thefux".to_string())
+        };
+        let serialized = serde_json::to_string(&bucket).unwrap();
+        assert!(serialized.contains(r#""file_size_limit":536870912000,"#));
+
+        let update = BucketUpdate {
+            file_size_limit: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"file_size_limit":18446744073709551615}"#
+        );
+    }
+
+    #[test]
+    fn test_validate_new_bucket() {
+        assert!(NewBucket::new("the-fux_2.0".to_string()).validate().is_ok());
+        let message = |name: String| NewBucket::new(name).validate().unwrap_err().message;
+        assert_eq!(
+            message("TheFux".to_string()),
+            BucketNameError::InvalidCharacter('T').to_string()
+        );
+        assert_eq!(
+            message("the fux".to_string()),
+            BucketNameError::InvalidCharacter(' ').to_string()
+        );
+        assert_eq!(message(String::new()), BucketNameError::Empty.to_string());
+        assert_eq!(
+            message("a".repeat(101)),
+            BucketNameError::TooLong {
+                length: 101,
+                max: BUCKET_NAME_MAX_LENGTH
+            }
+            .to_string()
+        );
+
+        let error = NewBucket {
+            id: Some("The Fux".to_string()),
+            ..NewBucket::new("thefux".to_string())
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(error.error, "InvalidBucketName");
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,4 +8,177 @@ pub struct Error {
     pub status_code: String,
     pub error: String,
     pub message: String,
+    /// The operation which failed as `METHOD /path`, query parameters are never included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(mut value: reqwest::Error) -> Self {
+        // the query of signed and presigned urls carries credentials, keep it out of the message
+        if let Some(mut url) = value.url().cloned() {
+            url.set_query(None);
+            url.set_fragment(None);
+            value = value.with_url(url);
+        }
+        Self {
+            status_code: value
+                .status()
+                .map(|status| status.as_str().to_string())
+                .unwrap_or_default(),
+            error: "RequestError".to_string(),
+            message: value.to_string(),
+            context: None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self {
+            status_code: String::new(),
+            error: "IoError".to_string(),
+            message: value.to_string(),
+            context: None,
+        }
+    }
+}
+
+/// Reason a bucket name is rejected before sending the request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BucketNameError {
+    Empty,
+    TooLong { length: usize, max: usize },
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for BucketNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the bucket name is empty"),
+            Self::TooLong { length, max } => write!(
+                f,
+                "the bucket name has {} characters, at most {} are allowed",
+                length, max
+            ),
+            Self::InvalidCharacter(c) => write!(
+                f,
+                "the bucket name contains {:?}, only lowercase letters, digits, '.', '-' and '_' are allowed",
+                c
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BucketNameError {}
+
+impl From<BucketNameError> for Error {
+    fn from(value: BucketNameError) -> Self {
+        Self {
+            status_code: "400".to_string(),
+            error: "InvalidBucketName".to_string(),
+            message: value.to_string(),
+            context: None,
+        }
+    }
+}
+
+/// Coarse classification of an `Error` returned by the storage api
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Duplicate,
+    NotFound,
+    FileTooLarge,
+    NotAnImage,
+    Unauthorized,
+    InvalidRequest,
+    PreconditionFailed,
+    Other,
+}
+
+impl Error {
+    /// Maps the error returned by the storage api to an `ErrorKind`
+    ///
+    /// The `error` field is checked first, the `status_code` is used as a fallback.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::model::errors::{Error, ErrorKind};
+    ///
+    /// let error = Error {
+    ///     status_code: "409".to_string(),
+    ///     error: "Duplicate".to_string(),
+    ///     message: "The resource already exists".to_string(),
+    ///     context: None,
+    /// };
+    /// assert_eq!(error.kind(), ErrorKind::Duplicate);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self.error.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "duplicate" => return ErrorKind::Duplicate,
+            "not_found" => return ErrorKind::NotFound,
+            "filetoolarge" | "payload_too_large" => return ErrorKind::FileTooLarge,
+            "notanimage" => return ErrorKind::NotAnImage,
+            "unauthorized" => return ErrorKind::Unauthorized,
+            "invalid_request" | "invalidrequest" => return ErrorKind::InvalidRequest,
+            "precondition_failed" | "preconditionfailed" => return ErrorKind::PreconditionFailed,
+            _ => {}
+        }
+
+        match self.status_code.as_str() {
+            "409" => ErrorKind::Duplicate,
+            "404" => ErrorKind::NotFound,
+            "413" => ErrorKind::FileTooLarge,
+            "401" | "403" => ErrorKind::Unauthorized,
+            "400" => ErrorKind::InvalidRequest,
+            "412" => ErrorKind::PreconditionFailed,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn error(status_code: &str, error: &str) -> Error {
+        Error {
+            status_code: status_code.to_string(),
+            error: error.to_string(),
+            message: String::new(),
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(error("409", "Duplicate").kind(), ErrorKind::Duplicate);
+        assert_eq!(error("404", "not_found").kind(), ErrorKind::NotFound);
+        assert_eq!(error("400", "Not Found").kind(), ErrorKind::NotFound);
+        assert_eq!(error("409", "").kind(), ErrorKind::Duplicate);
+        assert_eq!(
+            error("413", "Payload too large").kind(),
+            ErrorKind::FileTooLarge
+        );
+        assert_eq!(
+            error("412", "Precondition Failed").kind(),
+            ErrorKind::PreconditionFailed
+        );
+        assert_eq!(error("412", "").kind(), ErrorKind::PreconditionFailed);
+        assert_eq!(error("500", "internal").kind(), ErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn test_request_error_without_query() {
+        let error: Error = reqwest::Client::new()
+            .get("http://127.0.0.1:1/object/sign/thefux/btc.pdf?token=secret")
+            .send()
+            .await
+            .unwrap_err()
+            .into();
+
+        assert_eq!(error.error, "RequestError");
+        assert!(error.message.contains("/object/sign/thefux/btc.pdf"));
+        assert!(!error.message.contains("secret"));
+    }
 }
@@ -1,4 +1,9 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::errors;
 
 #[derive(Debug, Deserialize)]
 pub struct Response {
@@ -10,7 +15,189 @@ pub struct SingedUrlToUpload {
     pub url: String,
 }
 
-#[derive(Debug, Serialize)]
+/// response of `create_signed_url`, the signed url is relative to the storage url
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SignedUrl {
+    #[serde(rename = "signedURL")]
+    pub signed_url: String,
+}
+
+impl SignedUrl {
+    /// The signed url resolved against the base url of the storage, see `Storage::base_url`.
+    ///
+    /// # Example
+    /// ```
+    /// use url::Url;
+    /// use supabase_storage::model::object::SignedUrl;
+    ///
+    /// let signed = SignedUrl {
+    ///     signed_url: "/object/sign/thefux/btc.pdf?token=abc".to_string(),
+    /// };
+    /// let base = Url::parse("https://abcd1234.supabase.co/storage/v1").unwrap();
+    /// assert_eq!(
+    ///     signed.absolute_url(&base),
+    ///     "https://abcd1234.supabase.co/storage/v1/object/sign/thefux/btc.pdf?token=abc"
+    /// );
+    /// ```
+    pub fn absolute_url(&self, base: &Url) -> String {
+        if Url::parse(&self.signed_url).is_ok() {
+            return self.signed_url.clone();
+        }
+        format!(
+            "{}/{}",
+            base.as_str().trim_end_matches('/'),
+            self.signed_url.trim_start_matches('/')
+        )
+    }
+}
+
+/// response of a successful upload
+#[derive(Debug, Deserialize)]
+pub struct UploadResult {
+    #[serde(rename = "Key")]
+    pub key: String,
+    #[serde(rename = "Id")]
+    pub id: Option<String>,
+}
+
+/// an entry returned when listing objects, folders have no `id`
+#[derive(Debug, Deserialize)]
+pub struct ObjectMetadata {
+    pub name: String,
+    pub id: Option<String>,
+    pub updated_at: Option<String>,
+    pub created_at: Option<String>,
+    pub last_accessed_at: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// a page of the `list-v2` endpoint, see `list_objects_after`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListObjectsPage {
+    #[serde(default)]
+    pub has_next: bool,
+    #[serde(default)]
+    pub folders: Vec<ObjectMetadata>,
+    #[serde(default)]
+    pub objects: Vec<ObjectMetadata>,
+    /// Opaque cursor of the next page, `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// response of the object info endpoints
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub size: Option<u64>,
+    pub mimetype: Option<String>,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// an object removed by `delete_objects`
+#[derive(Debug, Deserialize)]
+pub struct DeletedObject {
+    pub name: String,
+    pub bucket_id: String,
+    pub id: Option<String>,
+    pub owner: Option<String>,
+    pub version: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub last_accessed_at: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// progress of an upload, emitted whenever a chunk of the body is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub bytes_sent: u64,
+    pub total: u64,
+}
+
+/// a page of the S3 compatible `ListObjectsV2` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3ListObjects {
+    #[serde(default)]
+    pub contents: Vec<S3Object>,
+    #[serde(default)]
+    pub common_prefixes: Vec<S3CommonPrefix>,
+    #[serde(default)]
+    pub is_truncated: bool,
+    /// token to request the next page, `None` on the last page
+    pub next_continuation_token: Option<String>,
+}
+
+/// an object of the S3 compatible listing
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3Object {
+    pub key: String,
+    pub size: Option<u64>,
+    pub last_modified: Option<String>,
+    #[serde(rename = "ETag")]
+    pub etag: Option<String>,
+}
+
+/// a folder of the S3 compatible listing
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3CommonPrefix {
+    pub prefix: String,
+}
+
+/// response of initiating an S3 compatible multipart upload
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3MultipartUpload {
+    pub bucket: String,
+    pub key: String,
+    pub upload_id: String,
+}
+
+/// a part of an S3 compatible multipart upload, the `ETag` is returned when uploading the part
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct S3CompletedPart {
+    pub part_number: u32,
+    #[serde(rename = "ETag")]
+    pub etag: String,
+}
+
+/// outcome of deleting all objects under a prefix
+#[derive(Debug, Default)]
+pub struct DeletePrefixResult {
+    /// number of deleted objects
+    pub deleted: usize,
+    /// errors of the batches which couldn't be deleted
+    pub failures: Vec<errors::Error>,
+}
+
+/// content of an object along with the metadata commonly needed by caching layers
+#[derive(Debug)]
+pub struct ObjectWithMeta {
+    pub body: Bytes,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// body of a response, parsed depending on its `Content-Type`
+#[derive(Debug)]
+pub enum Either<T> {
+    /// an `application/json` body
+    Json(T),
+    /// any other body
+    Bytes(Bytes),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MoveCopyObject {
     #[serde(rename = "bucketId")]
     pub bucket_id: String,
@@ -18,4 +205,210 @@ pub struct MoveCopyObject {
     pub source_key: String,
     #[serde(rename = "destinationKey")]
     pub destination_key: String,
+    /// keep the metadata of the source when copying, ignored when moving
+    #[serde(rename = "copyMetadata", skip_serializing_if = "Option::is_none")]
+    pub copy_metadata: Option<bool>,
+}
+
+/// claims of the token of a signed url
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SignedTokenClaims {
+    /// the signed object as `bucket/path`
+    pub url: String,
+    /// issued at, in seconds since the unix epoch
+    pub iat: u64,
+    /// expiry, in seconds since the unix epoch
+    pub exp: u64,
+}
+
+/// Decodes the claims of a signed url token, or of the `token` query parameter of a signed url,
+/// either absolute or relative like the `signedURL` returned by the api.
+///
+/// The signature is not verified, use this to inspect a token, e.g. to debug expired links.
+///
+/// # Example
+/// ```
+/// use supabase_storage::model::object::decode_signed_token;
+///
+/// let claims = decode_signed_token(
+///     "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJ1cmwiOiJ0aGVmdXgvYnRjLnBkZiIsImlhdCI6MTcwNDcyMDI1NywiZXhwIjoxNzA0NzIzODU3fQ.signature",
+/// )
+/// .unwrap();
+/// assert_eq!(claims.url, "thefux/btc.pdf");
+/// assert_eq!(claims.exp - claims.iat, 3600);
+/// ```
+pub fn decode_signed_token(token: &str) -> Result<SignedTokenClaims, errors::Error> {
+    let invalid_token = |message: String| errors::Error {
+        status_code: String::new(),
+        error: "InvalidToken".to_string(),
+        message,
+        context: None,
+    };
+
+    // a jwt never contains `/` or `?`, anything else is an absolute or relative signed url,
+    // e.g. the `signedURL` returned by the api
+    let token = if token.contains(['/', '?']) {
+        let base = Url::parse("http://localhost").expect("the base url is valid");
+        base.join(token)
+            .map_err(|error| invalid_token(format!("the signed url is invalid: {}", error)))?
+            .query_pairs()
+            .find(|(key, _)| key == "token")
+            .map(|(_, value)| value.into_owned())
+            .ok_or_else(|| invalid_token("the url has no token query parameter".into()))?
+    } else {
+        token.to_string()
+    };
+
+    let payload = token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| invalid_token("the token is not a jwt".into()))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|error| invalid_token(format!("the token payload is not base64: {}", error)))?;
+
+    serde_json::from_slice(&payload)
+        .map_err(|error| invalid_token(format!("the token claims are invalid: {}", error)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_upload_result() {
+        let result: UploadResult = serde_json::from_str(
+            r#"{"Key":"thefux/btc.pdf","Id":"3b7f3a1c-4f8e-4f0a-9d1a-8c7f0e6b2a11"}"#,
+        )
+        .unwrap();
+        assert_eq!(result.key, "thefux/btc.pdf");
+        assert_eq!(
+            result.id.as_deref(),
+            Some("3b7f3a1c-4f8e-4f0a-9d1a-8c7f0e6b2a11")
+        );
+
+        let result: UploadResult = serde_json::from_str(r#"{"Key":"thefux/btc.pdf"}"#).unwrap();
+        assert_eq!(result.id, None);
+    }
+
+    #[test]
+    fn test_deserialize_object_info() {
+        let info: ObjectInfo = serde_json::from_str(
+            r#"{
+                "id": "3b7f3a1c-4f8e-4f0a-9d1a-8c7f0e6b2a11",
+                "name": "btc.pdf",
+                "version": "6c1c3e8a-0d1b-4c5e-8f2a-1b9d7e4c3a21",
+                "bucketId": "thefux",
+                "size": 184292,
+                "mimetype": "application/pdf",
+                "cacheControl": "max-age=3600",
+                "etag": "\"e5d1b1a0c3f8f4d2a7b6c9e0f1a2b3c4\"",
+                "metadata": {},
+                "lastModified": "2024-01-08T13:24:17.000Z",
+                "createdAt": "2024-01-08T13:24:17.318Z"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(info.name, "btc.pdf");
+        assert_eq!(info.size, Some(184292));
+        assert_eq!(info.mimetype.as_deref(), Some("application/pdf"));
+        assert_eq!(info.cache_control.as_deref(), Some("max-age=3600"));
+        assert_eq!(
+            info.etag.as_deref(),
+            Some("\"e5d1b1a0c3f8f4d2a7b6c9e0f1a2b3c4\"")
+        );
+        assert_eq!(
+            info.last_modified.as_deref(),
+            Some("2024-01-08T13:24:17.000Z")
+        );
+        assert_eq!(info.created_at.as_deref(), Some("2024-01-08T13:24:17.318Z"));
+    }
+
+    #[test]
+    fn test_deserialize_deleted_objects() {
+        let deleted: Vec<DeletedObject> = serde_json::from_str(
+            r#"[{
+                "bucket_id": "thefux",
+                "name": "btc.pdf",
+                "owner": "",
+                "id": "3b7f3a1c-4f8e-4f0a-9d1a-8c7f0e6b2a11",
+                "updated_at": "2024-01-08T13:24:17.318Z",
+                "created_at": "2024-01-08T13:24:17.318Z",
+                "last_accessed_at": "2024-01-08T13:24:17.318Z",
+                "metadata": {"size": 184292, "mimetype": "application/pdf"},
+                "version": "6c1c3e8a-0d1b-4c5e-8f2a-1b9d7e4c3a21"
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].name, "btc.pdf");
+        assert_eq!(deleted[0].bucket_id, "thefux");
+        assert_eq!(
+            deleted[0].id.as_deref(),
+            Some("3b7f3a1c-4f8e-4f0a-9d1a-8c7f0e6b2a11")
+        );
+        assert_eq!(deleted[0].metadata.as_ref().unwrap()["size"], 184292);
+    }
+
+    #[test]
+    fn test_deserialize_signed_url() {
+        let signed: SignedUrl = serde_json::from_str(
+            r#"{"signedURL":"/object/sign/thefux/btc.pdf?token=eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            signed.signed_url,
+            "/object/sign/thefux/btc.pdf?token=eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9"
+        );
+
+        for base in [
+            "https://abcd1234.supabase.co/storage/v1",
+            "https://abcd1234.supabase.co/storage/v1/",
+        ] {
+            assert_eq!(
+                signed.absolute_url(&Url::parse(base).unwrap()),
+                "https://abcd1234.supabase.co/storage/v1/object/sign/thefux/btc.pdf?token=eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9"
+            );
+        }
+
+        let absolute = SignedUrl {
+            signed_url: "https://cdn.example.com/object/sign/thefux/btc.pdf?token=abc".to_string(),
+        };
+        assert_eq!(
+            absolute.absolute_url(&Url::parse("http://localhost").unwrap()),
+            absolute.signed_url
+        );
+    }
+
+    #[test]
+    fn test_decode_signed_token() {
+        let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJ1cmwiOiJ0aGVmdXgvYnRjLnBkZiIsImlhdCI6MTcwNDcyMDI1NywiZXhwIjoxNzA0NzIzODU3fQ.signature";
+        let expected = SignedTokenClaims {
+            url: "thefux/btc.pdf".to_string(),
+            iat: 1704720257,
+            exp: 1704723857,
+        };
+
+        assert_eq!(decode_signed_token(token).unwrap(), expected);
+        assert_eq!(
+            decode_signed_token(&format!(
+                "https://abcd1234.supabase.co/storage/v1/object/sign/thefux/btc.pdf?token={}",
+                token
+            ))
+            .unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode_signed_token(&format!("/object/sign/thefux/btc.pdf?token={}", token)).unwrap(),
+            expected
+        );
+
+        assert_eq!(
+            decode_signed_token("not-a-token").unwrap_err().error,
+            "InvalidToken"
+        );
+        assert!(decode_signed_token("https://abcd1234.supabase.co/storage/v1").is_err());
+    }
 }
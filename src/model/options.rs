@@ -1,6 +1,12 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::header::HeaderValue;
 use serde::{Serialize, Serializer};
+use std::time::Duration;
 
-#[derive(Debug, Serialize)]
+use super::errors;
+use crate::util::rate_limit::RateLimiter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum Resize {
     #[serde(rename = "cover")]
     Cover,
@@ -20,12 +26,14 @@ impl From<Resize> for &str {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum Format {
     #[serde(rename = "origin")]
     Origin,
     #[serde(rename = "avif")]
     Avif,
+    #[serde(rename = "webp")]
+    Webp,
 }
 
 impl From<Format> for &str {
@@ -33,31 +41,65 @@ impl From<Format> for &str {
         match value {
             Format::Avif => "avif",
             Format::Origin => "origin",
+            Format::Webp => "webp",
         }
     }
 }
 
 /// * format: Specify the format of the image requested.
-///           When using 'origin' we force the format to be the same as the original image.
-///           When this option is not passed in, images are optimized to modern image formats like Webp.
+///   When using 'origin' we force the format to be the same as the original image.
+///   When this option is not passed in, images are optimized to modern image formats like Webp.
 /// * height: The height of the image in pixels.
 /// * quality: Set the quality of the returned image.
-///            A number from 20 to 100, with 100 being the highest quality.
-///            Defaults to 80
+///   A number from 20 to 100, with 100 being the highest quality.
+///   Defaults to 80
 /// * resize: The resize mode can be cover, contain or fill.
-///           Defaults to cover.
-///           Cover resizes the image to maintain it's aspect ratio while filling the entire width and height.
-///           Contain resizes the image to maintain it's aspect ratio while fitting the entire image within the width and height. Fill resizes the image to fill the entire width and height. If the object's aspect ratio does not match the width and height, the image will be stretched to fit.
+///   Defaults to cover.
+///   Cover resizes the image to maintain it's aspect ratio while filling the entire width and height.
+///   Contain resizes the image to maintain it's aspect ratio while fitting the entire image within the width and height. Fill resizes the image to fill the entire width and height. If the object's aspect ratio does not match the width and height, the image will be stretched to fit.
 /// * width: The width of the image in pixels.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Transform {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<Format>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub quality: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub resize: Option<Resize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<u32>,
 }
 
+impl Transform {
+    /// Converts the image to WebP with a quality of `75`, for smaller images without resizing.
+    ///
+    /// When `format` is omitted, the storage already serves WebP to clients whose `Accept`
+    /// header allows it and the original format otherwise, `Format::Origin` disables this.
+    /// Setting the format forces WebP regardless of the `Accept` header.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::model::options::{Format, Transform};
+    ///
+    /// let transform = Transform {
+    ///     width: Some(200),
+    ///     ..Transform::webp_optimized()
+    /// };
+    /// assert_eq!(transform.format, Some(Format::Webp));
+    /// ```
+    pub fn webp_optimized() -> Self {
+        Self {
+            format: Some(Format::Webp),
+            height: None,
+            quality: Some(75),
+            resize: None,
+            width: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct Options {
     pub download: Option<bool>,
@@ -65,14 +107,18 @@ pub struct Options {
 }
 
 /// * cache_control: The number of seconds the asset is cached in the browser and in the Supabase CDN.
-///                  This is set in the `Cache-Control: max-age=<seconds>` header. Defaults to 3600 seconds
+///   This is set in the `Cache-Control: max-age=<seconds>` header. Defaults to 3600 seconds
 /// * content_type: the `Content-Type` header value.
-///                 Should be specified if using a `fileBody` that is neither `Blob` nor `File` nor `FormData`,
-///                 otherwise will default to `text/plain;charset=UTF-8`.
+///   Should be specified if using a `fileBody` that is neither `Blob` nor `File` nor `FormData`,
+///   otherwise will default to `text/plain;charset=UTF-8`.
+///   `None` guesses it from the object name, an empty string sends no `Content-Type` header
+///   so the server picks the default.
 /// * upsert: When upsert is set to true, the file is overwritten if it exists.
-///           When set to false, an error is thrown if the object already exists.
-///           Defaults to false.
-#[derive(Debug, Serialize)]
+///   When set to false, an error is thrown if the object already exists.
+///   Defaults to false.
+/// * content_disposition: the `Content-Disposition` header value stored with the object,
+///   e.g. `attachment; filename="report.pdf"` to download it under another name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct FileOptions {
     #[serde(serialize_with = "serialize_cache_control")]
     #[serde(rename = "cache-control")]
@@ -80,6 +126,363 @@ pub struct FileOptions {
     #[serde(rename = "content-type")]
     pub content_type: Option<String>,
     pub upsert: Option<bool>,
+    #[serde(rename = "content-disposition")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_disposition: Option<String>,
+}
+
+/// * bucket_name: The bucket the object is uploaded to.
+/// * object_name: The name of the uploaded object.
+/// * content_type: the `Content-Type` of the object.
+/// * cache_control: The number of seconds the asset is cached in the browser and in the Supabase CDN.
+/// * upsert: When upsert is set to true, the file is overwritten if it exists.
+#[derive(Debug, Clone)]
+pub struct ResumableUploadOptions {
+    pub bucket_name: String,
+    pub object_name: String,
+    pub content_type: Option<String>,
+    pub cache_control: Option<u64>,
+    pub upsert: Option<bool>,
+}
+
+impl ResumableUploadOptions {
+    /// Creates options to upload `object_name` into `bucket_name`
+    pub fn new(bucket_name: &str, object_name: &str) -> Self {
+        Self {
+            bucket_name: bucket_name.to_string(),
+            object_name: object_name.to_string(),
+            content_type: None,
+            cache_control: None,
+            upsert: None,
+        }
+    }
+
+    /// The value of the TUS `Upload-Metadata` header,
+    /// comma separated `key base64(value)` pairs.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::model::options::ResumableUploadOptions;
+    ///
+    /// let options = ResumableUploadOptions::new("thefux", "btc.pdf");
+    /// assert_eq!(
+    ///     options.upload_metadata(),
+    ///     "bucketName dGhlZnV4,objectName YnRjLnBkZg=="
+    /// );
+    /// ```
+    pub fn upload_metadata(&self) -> String {
+        let mut pairs = vec![
+            ("bucketName", self.bucket_name.clone()),
+            ("objectName", self.object_name.clone()),
+        ];
+        if let Some(content_type) = &self.content_type {
+            pairs.push(("contentType", content_type.clone()));
+        }
+        if let Some(cache_control) = self.cache_control {
+            pairs.push(("cacheControl", cache_control.to_string()));
+        }
+
+        pairs
+            .into_iter()
+            .map(|(key, value)| format!("{} {}", key, STANDARD.encode(value)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// column used to sort the listed objects
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortColumn {
+    Name,
+    UpdatedAt,
+    CreatedAt,
+    LastAccessedAt,
+    /// any column not known by this crate
+    Other(String),
+}
+
+impl SortColumn {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SortColumn::Name => "name",
+            SortColumn::UpdatedAt => "updated_at",
+            SortColumn::CreatedAt => "created_at",
+            SortColumn::LastAccessedAt => "last_accessed_at",
+            SortColumn::Other(column) => column,
+        }
+    }
+}
+
+impl Serialize for SortColumn {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum SortOrder {
+    #[serde(rename = "asc")]
+    Asc,
+    #[serde(rename = "desc")]
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortBy {
+    pub column: SortColumn,
+    pub order: SortOrder,
+}
+
+/// * expires_in: The number of seconds until the signed url expires.
+/// * transform: Transformation applied to the signed object.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUrlOptions {
+    pub expires_in: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+}
+
+impl SignedUrlOptions {
+    /// Creates options expiring after `duration`, truncated to whole seconds
+    ///
+    /// Durations shorter than a second are rejected with an `InvalidRequest` error.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use supabase_storage::model::options::SignedUrlOptions;
+    ///
+    /// let options = SignedUrlOptions::expires_in_duration(Duration::from_secs(60)).unwrap();
+    /// assert_eq!(options.expires_in, 60);
+    /// assert!(SignedUrlOptions::expires_in_duration(Duration::from_millis(500)).is_err());
+    /// ```
+    pub fn expires_in_duration(duration: Duration) -> Result<Self, errors::Error> {
+        if duration.as_secs() == 0 {
+            return Err(errors::Error {
+                status_code: "400".to_string(),
+                error: "InvalidRequest".to_string(),
+                message: format!(
+                    "the signed url must be valid for at least one second, got {:?}",
+                    duration
+                ),
+                context: None,
+            });
+        }
+
+        Ok(Self {
+            expires_in: duration.as_secs(),
+            transform: None,
+        })
+    }
+}
+
+/// * expires_in: The number of seconds until the signed urls expire.
+/// * paths: The objects to sign.
+/// * transform: Transformation applied to all the signed objects.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUrlsOptions {
+    pub expires_in: u64,
+    pub paths: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+}
+
+/// * prefix: The folder path.
+/// * limit: The number of files to return.
+/// * offset: The starting position.
+/// * sort_by: The column to sort by and the sort order.
+/// * search: Only return objects whose name contains the search term.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListObjectsOptions {
+    pub prefix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<SortBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+}
+
+impl ListObjectsOptions {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            limit: None,
+            offset: None,
+            sort_by: None,
+            search: None,
+        }
+    }
+
+    /// Sets the search term used to filter the objects by name.
+    pub fn search(mut self, search: &str) -> Self {
+        self.search = Some(search.to_string());
+        self
+    }
+}
+
+/// Keyset pagination of the `list-v2` endpoint, the page starts after `cursor` instead of
+/// skipping `offset` objects, so the cost of a page doesn't grow with its position.
+///
+/// * prefix: The folder path.
+/// * limit: The number of files to return.
+/// * cursor: The `next_cursor` of the previous `ListObjectsPage`, `None` for the first page.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListObjectsAfterOptions {
+    pub prefix: String,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// * limit: The maximum number of requests running at the same time, `0` runs them one at a time.
+/// * ordered: Return the results in the order of the inputs.
+///   When set to false, results are returned as soon as they complete.
+/// * rate_limit: Throttle the requests to stay below the rate limit of the storage.
+///
+/// Build it with `Concurrency::new` and the setters.
+///
+/// # Example
+/// ```
+/// use supabase_storage::{model::options::Concurrency, util::rate_limit::RateLimiter};
+///
+/// let concurrency = Concurrency::new(8)
+///     .ordered(false)
+///     .rate_limit(RateLimiter::new(10.0).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Concurrency {
+    pub limit: usize,
+    pub ordered: bool,
+    pub rate_limit: Option<RateLimiter>,
+}
+
+impl Concurrency {
+    /// Runs up to `limit` requests at the same time, in order and without a rate limit.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// Sets whether the results are returned in the order of the inputs.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Throttles the requests with `rate_limit`, clones of a limiter share the same budget.
+    pub fn rate_limit(mut self, rate_limit: RateLimiter) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+}
+
+impl Default for Concurrency {
+    fn default() -> Self {
+        Self {
+            limit: 4,
+            ordered: true,
+            rate_limit: None,
+        }
+    }
+}
+
+/// The `Cache-Control` value of the `cache_control` seconds, shared by the serialized
+/// `FileOptions` and the upload headers.
+pub(crate) fn cache_control_value(seconds: u64) -> String {
+    format!("max-age={}", seconds)
+}
+
+/// The `Content-Disposition` header value of `content_disposition`.
+///
+/// A non-ASCII `filename` like `résumé.pdf` is sent as an RFC 5987 `filename*=UTF-8''…`
+/// parameter, next to an ASCII `filename` fallback for older clients, any other non-ASCII
+/// or control character is an error.
+pub(crate) fn content_disposition_value(
+    content_disposition: &str,
+) -> Result<HeaderValue, errors::Error> {
+    let value = if content_disposition.is_ascii() {
+        content_disposition.to_string()
+    } else {
+        rewrite_non_ascii_filename(content_disposition)
+    };
+
+    HeaderValue::from_str(&value)
+        .ok()
+        .filter(|_| value.is_ascii())
+        .ok_or_else(|| errors::Error {
+            status_code: "400".to_string(),
+            error: "InvalidContentDisposition".to_string(),
+            message: format!("{} is not a valid Content-Disposition", content_disposition),
+            context: None,
+        })
+}
+
+fn rewrite_non_ascii_filename(content_disposition: &str) -> String {
+    content_disposition
+        .split(';')
+        .map(|param| {
+            let param = param.trim();
+            match param.split_once('=') {
+                Some((name, filename)) if name.trim().eq_ignore_ascii_case("filename") => {
+                    let filename = filename.trim().trim_matches('"');
+                    let fallback: String = filename
+                        .chars()
+                        .map(|c| {
+                            if c.is_ascii_graphic() || c == ' ' {
+                                c
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect();
+                    format!(
+                        "filename=\"{}\"; filename*=UTF-8''{}",
+                        fallback,
+                        encode_ext_value(filename)
+                    )
+                }
+                _ => param.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Percent-encodes everything but the `attr-char`s of RFC 5987.
+fn encode_ext_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z'
+            | b'A'..=b'Z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
 }
 
 fn serialize_cache_control<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
@@ -87,7 +490,7 @@ where
     S: Serializer,
 {
     if let Some(val) = value {
-        serializer.serialize_str(&format!("max-age={}", val))
+        serializer.serialize_str(&cache_control_value(*val))
     } else {
         serializer.serialize_none()
     }
@@ -96,12 +499,32 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+
+    #[test]
+    fn test_content_disposition_value() {
+        assert_eq!(
+            content_disposition_value("attachment; filename=\"report.pdf\"").unwrap(),
+            "attachment; filename=\"report.pdf\""
+        );
+        assert_eq!(
+            content_disposition_value("attachment; filename=\"résumé 2024.pdf\"").unwrap(),
+            "attachment; filename=\"r_sum_ 2024.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9%202024.pdf"
+        );
+        assert_eq!(
+            content_disposition_value("attachment; name=\"ré\"")
+                .unwrap_err()
+                .error,
+            "InvalidContentDisposition"
+        );
+    }
+
     #[test]
     fn test_serialize_file_options() {
         let options = FileOptions {
             cache_control: Some(1000),
             content_type: Some("application/pdf".to_string()),
             upsert: Some(true),
+            content_disposition: None,
         };
         let serialized = serde_json::to_string(&options).unwrap();
         assert_eq!(
@@ -109,4 +532,109 @@ mod test {
             r#"{"cache-control":"max-age=1000","content-type":"application/pdf","upsert":true}"#
         );
     }
+
+    #[test]
+    fn test_serialize_transform_format_only() {
+        let transform = Transform {
+            format: Some(Format::Webp),
+            height: None,
+            quality: None,
+            resize: None,
+            width: None,
+        };
+        assert_eq!(serde_qs::to_string(&transform).unwrap(), "format=webp");
+    }
+
+    #[test]
+    fn test_serialize_transform_webp_optimized() {
+        assert_eq!(
+            serde_qs::to_string(&Transform::webp_optimized()).unwrap(),
+            "format=webp&quality=75"
+        );
+    }
+
+    #[test]
+    fn test_serialize_signed_urls_options() {
+        let options = SignedUrlsOptions {
+            expires_in: 3600,
+            paths: vec!["a.png".to_string(), "b.png".to_string()],
+            transform: Some(Transform {
+                format: None,
+                height: Some(100),
+                quality: None,
+                resize: Some(Resize::Contain),
+                width: Some(100),
+            }),
+        };
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"expiresIn":3600,"paths":["a.png","b.png"],"transform":{"height":100,"resize":"contain","width":100}}"#
+        );
+    }
+
+    #[test]
+    fn test_resumable_upload_metadata() {
+        let options = ResumableUploadOptions {
+            content_type: Some("application/pdf".to_string()),
+            cache_control: Some(3600),
+            ..ResumableUploadOptions::new("thefux", "btc.pdf")
+        };
+        assert_eq!(
+            options.upload_metadata(),
+            "bucketName dGhlZnV4,objectName YnRjLnBkZg==,contentType YXBwbGljYXRpb24vcGRm,cacheControl MzYwMA=="
+        );
+    }
+
+    #[test]
+    fn test_signed_url_options_expires_in_duration() {
+        let options = SignedUrlOptions::expires_in_duration(Duration::from_secs(3600)).unwrap();
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"expiresIn":3600}"#
+        );
+
+        let error = SignedUrlOptions::expires_in_duration(Duration::ZERO).unwrap_err();
+        assert_eq!(error.kind(), errors::ErrorKind::InvalidRequest);
+        assert!(SignedUrlOptions::expires_in_duration(Duration::from_millis(999)).is_err());
+    }
+
+    #[test]
+    fn test_serialize_sort_column() {
+        let columns = [
+            (SortColumn::Name, r#""name""#),
+            (SortColumn::UpdatedAt, r#""updated_at""#),
+            (SortColumn::CreatedAt, r#""created_at""#),
+            (SortColumn::LastAccessedAt, r#""last_accessed_at""#),
+            (SortColumn::Other("size".to_string()), r#""size""#),
+        ];
+        for (column, expected) in columns {
+            assert_eq!(serde_json::to_string(&column).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_serialize_list_objects_options_search() {
+        let options = ListObjectsOptions::new("2023").search("invoice");
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"prefix":"2023","search":"invoice"}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_list_objects_options() {
+        let options = ListObjectsOptions {
+            limit: Some(100),
+            offset: Some(0),
+            sort_by: Some(SortBy {
+                column: SortColumn::Name,
+                order: SortOrder::Asc,
+            }),
+            ..ListObjectsOptions::new("folder")
+        };
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"prefix":"folder","limit":100,"offset":0,"sortBy":{"column":"name","order":"asc"}}"#
+        );
+    }
 }
@@ -0,0 +1,13 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// description of a request built by `Executor::dry_run`, credentials are redacted
+#[derive(Debug, Serialize)]
+pub struct RequestParts {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    /// the body as text, a placeholder for binary, compressed or streamed bodies
+    pub body_summary: Option<String>,
+}
@@ -0,0 +1,82 @@
+//! Re-exports of the commonly used types.
+//!
+//! # Example
+//! ```
+//! use supabase_storage::prelude::*;
+//!
+//! let storage = Storage::new("https://your_project_path/storage/v1");
+//! let executor = storage
+//!     .from()
+//!     .get_object_with_transform("thefux", "test.png", Transform::webp_optimized());
+//! ```
+
+pub use crate::{
+    config::SupabaseConfig,
+    model::{
+        bucket::{BucketDetails, BucketUpdate, NewBucket},
+        errors::ErrorKind,
+        object::{ObjectInfo, ObjectMetadata, Response, SignedUrl, UploadResult},
+        options::{FileOptions, Format, ListObjectsOptions, Resize, Transform},
+    },
+    BucketHandle, Storage, StorageBuilder,
+};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prelude() {
+        let storage: Storage = SupabaseConfig {
+            supabase_url_storage: "http://localhost/storage/v1".to_string(),
+            supabase_api_key: None,
+        }
+        .into();
+
+        let executor = storage.from().get_object_with_transform(
+            "thefux",
+            "test.png",
+            Transform {
+                format: Some(Format::Origin),
+                resize: Some(Resize::Cover),
+                ..Transform::webp_optimized()
+            },
+        );
+        assert_eq!(
+            executor.url().path(),
+            "/storage/v1/render/image/authenticated/thefux/test.png"
+        );
+
+        let executor = storage
+            .from()
+            .update_bucket_from(
+                "thefux",
+                BucketUpdate {
+                    public: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(executor.url().path(), "/storage/v1/bucket/thefux");
+
+        let _ = (
+            NewBucket::new("thefux".to_string()),
+            FileOptions {
+                cache_control: Some(3600),
+                content_type: None,
+                upsert: None,
+                content_disposition: None,
+            },
+            ListObjectsOptions::new("folder"),
+            ErrorKind::NotFound,
+        );
+        let _: Option<(
+            BucketDetails,
+            ObjectInfo,
+            ObjectMetadata,
+            Response,
+            SignedUrl,
+            UploadResult,
+        )> = None;
+    }
+}
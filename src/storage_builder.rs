@@ -0,0 +1,487 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header::HeaderMap, redirect::Policy, Client};
+
+use crate::{
+    build::{backend::HttpBackend, builder::TokenProvider},
+    config::SupabaseConfig,
+    model::{errors, options::FileOptions},
+    Storage,
+};
+
+/// Options of the internally constructed `reqwest::Client`.
+///
+/// Shared by `StorageBuilder` and the `Storage::with_*` client methods, so rebuilding the
+/// client for one option keeps the options set before. Unset options keep the reqwest defaults.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClientConfig {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) compression: Option<bool>,
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    pub(crate) pool_idle_timeout: Option<Option<Duration>>,
+    pub(crate) http2_prior_knowledge: bool,
+    pub(crate) https_only: bool,
+    pub(crate) redirect_policy: Option<Arc<Policy>>,
+}
+
+impl ClientConfig {
+    /// Builds a client with all the options at once.
+    pub(crate) fn build(&self) -> Result<Client, errors::Error> {
+        let mut client = Client::builder();
+        if let Some(compression) = self.compression {
+            client = client.gzip(compression).brotli(compression);
+        }
+        if let Some(timeout) = self.timeout {
+            client = client.timeout(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            client = client.user_agent(user_agent);
+        }
+        if let Some(max_idle_per_host) = self.pool_max_idle_per_host {
+            client = client.pool_max_idle_per_host(max_idle_per_host);
+        }
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            client = client.pool_idle_timeout(pool_idle_timeout);
+        }
+        if self.http2_prior_knowledge {
+            client = client.http2_prior_knowledge();
+        }
+        if self.https_only {
+            client = client.https_only(true);
+        }
+        if let Some(policy) = self.redirect_policy.clone() {
+            client = client.redirect(Policy::custom(move |attempt| policy.redirect(attempt)));
+        }
+
+        client.build().map_err(|error| errors::Error {
+            status_code: String::new(),
+            error: "InvalidConfig".to_string(),
+            message: format!("failed to build the client: {}", error),
+            context: None,
+        })
+    }
+}
+
+/// Accumulates the configuration of a `Storage`, the client is constructed once in `build`.
+pub struct StorageBuilder {
+    url: String,
+    api_version: Option<String>,
+    api_key: Option<String>,
+    headers: HeaderMap,
+    client: ClientConfig,
+    backend: Option<Arc<dyn HttpBackend>>,
+    compress_body: bool,
+    default_file_options: Option<FileOptions>,
+    token_provider: Option<TokenProvider>,
+}
+
+impl StorageBuilder {
+    /// Creates a new `StorageBuilder` for the storage at the given url.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The base URL for the storage.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::StorageBuilder;
+    ///
+    /// let storage = StorageBuilder::new("https://your_project_path/storage/v1")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn new<T>(url: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            url: url.into(),
+            api_version: None,
+            api_key: None,
+            headers: HeaderMap::new(),
+            client: ClientConfig::default(),
+            backend: None,
+            compress_body: false,
+            default_file_options: None,
+            token_provider: None,
+        }
+    }
+
+    /// Appends `/storage/{version}` to the url unless it already ends with it,
+    /// so the url can be given as the host, or the mount point of a self-hosted storage.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("https://selfhosted.example.com/api")
+    ///     .api_version("v1")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     storage.base_url().as_str(),
+    ///     "https://selfhosted.example.com/api/storage/v1"
+    /// );
+    /// ```
+    pub fn api_version(mut self, version: &str) -> Self {
+        self.api_version = Some(version.to_string());
+        self
+    }
+
+    /// Sets the api key sent in the `Authorization` and `apiKey` headers.
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Adds headers sent with every request, see `Storage::with_default_headers`.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Sets the timeout of every request, from connecting until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header of every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.client.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Enables transparent decompression of gzip and brotli encoded responses,
+    /// see `Storage::with_compression`. It's enabled by default, like for `Storage::new`.
+    pub fn compression(mut self) -> Self {
+        self.client.compression = Some(true);
+        self
+    }
+
+    /// Tunes the connection pool of the client, see `Storage::with_pool_config`.
+    pub fn pool_config(
+        mut self,
+        max_idle_per_host: usize,
+        pool_idle_timeout: Option<Duration>,
+    ) -> Self {
+        self.client.pool_max_idle_per_host = Some(max_idle_per_host);
+        self.client.pool_idle_timeout = Some(pool_idle_timeout);
+        self
+    }
+
+    /// Only speak HTTP/2 without negotiating it first, e.g. for a self-hosted storage speaking h2c.
+    ///
+    /// Only applies to the internally constructed client, requests sent through a `backend`
+    /// use the configuration of that backend.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("http://localhost:5000")
+    ///     .http2_prior_knowledge()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.client.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Refuses to send requests over plain http, e.g. to never send the api key unencrypted.
+    ///
+    /// `build` returns an `InvalidConfig` error for a plain http storage url. Refusing redirects
+    /// to plain http only applies to the internally constructed client, requests sent through a
+    /// `backend` use the configuration of that backend.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("https://your_project_path/storage/v1")
+    ///     .https_only()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn https_only(mut self) -> Self {
+        self.client.https_only = true;
+        self
+    }
+
+    /// Sets how redirects are followed, up to 10 redirects are followed by default.
+    ///
+    /// When following a redirect to another host, reqwest drops sensitive headers such as
+    /// `Authorization`, the request to the redirect target is sent without the api key.
+    /// Use `Policy::none()` to handle redirects of authenticated endpoints yourself.
+    ///
+    /// # Example
+    /// ```
+    /// use reqwest::redirect::Policy;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("https://your_project_path/storage/v1")
+    ///     .redirect_policy(Policy::none())
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn redirect_policy(mut self, policy: Policy) -> Self {
+        self.client.redirect_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Sends all requests through the given `HttpBackend`, see `Storage::with_backend`.
+    pub fn backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Gzip string request bodies, see `Storage::with_body_compression`.
+    pub fn body_compression(mut self) -> Self {
+        self.compress_body = true;
+        self
+    }
+
+    /// Sets the `FileOptions` used by uploads, see `Storage::with_default_file_options`.
+    pub fn default_file_options(mut self, file_options: FileOptions) -> Self {
+        self.default_file_options = Some(file_options);
+        self
+    }
+
+    /// Fetches the bearer token for every request, see `Storage::with_token_provider`.
+    pub fn token_provider(mut self, token_provider: TokenProvider) -> Self {
+        self.token_provider = Some(token_provider);
+        self
+    }
+
+    /// Constructs the `Storage`, building the client once with all the accumulated options.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Storage, errors::Error>` - The `Storage`, or an `InvalidConfig` error.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use supabase_storage::Storage;
+    ///
+    /// let storage = Storage::builder("https://your_project_path/storage/v1")
+    ///     .api_key("your-api-key")
+    ///     .timeout(Duration::from_secs(30))
+    ///     .user_agent("my-app/1.0")
+    ///     .compression()
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn build(self) -> Result<Storage, errors::Error> {
+        let url = match &self.api_version {
+            Some(version) => versioned_url(&self.url, version)?,
+            None => self.url,
+        };
+        let mut storage = Storage::try_new_with_config(SupabaseConfig {
+            supabase_url_storage: url,
+            supabase_api_key: self.api_key,
+        })?;
+        if self.client.https_only && storage.url.scheme() != "https" {
+            return Err(errors::Error {
+                status_code: String::new(),
+                error: "InvalidConfig".to_string(),
+                message: format!("the storage url {} is not https", storage.url),
+                context: None,
+            });
+        }
+
+        storage.client = self.client.build()?;
+        storage.client_config = self.client;
+        storage.headers.extend(self.headers);
+        storage.backend = self.backend;
+        storage.compress_body = self.compress_body;
+        storage.default_file_options = self.default_file_options;
+        storage.token_provider = self.token_provider;
+
+        Ok(storage)
+    }
+}
+
+/// Appends `/storage/{version}` to the url, unless it already ends with it.
+fn versioned_url(url: &str, version: &str) -> Result<String, errors::Error> {
+    let version = version.trim_matches('/');
+    if version.is_empty() || version.contains('/') {
+        return Err(errors::Error {
+            status_code: String::new(),
+            error: "InvalidConfig".to_string(),
+            message: format!("the api version {:?} is not a single path segment", version),
+            context: None,
+        });
+    }
+
+    let url = url.trim_end_matches('/');
+    let suffix = format!("/storage/{}", version);
+    if url.ends_with(&suffix) {
+        Ok(url.to_string())
+    } else {
+        Ok(format!("{}{}", url, suffix))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io::Write, time::Duration};
+
+    use flate2::{write::GzEncoder, Compression};
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        redirect::Policy,
+    };
+
+    use crate::Storage;
+
+    #[tokio::test]
+    async fn test_build_storage() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/bucket")
+            .match_header("user-agent", "my-app/1.0")
+            .match_header("x-forwarded-host", "storage.example.com")
+            .match_header("authorization", "Bearer key")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-host",
+            HeaderValue::from_static("storage.example.com"),
+        );
+        let storage = Storage::builder(server.url())
+            .api_key("key")
+            .timeout(Duration::from_secs(5))
+            .user_agent("my-app/1.0")
+            .default_headers(headers)
+            .build()
+            .unwrap();
+
+        let response = storage.from().get_buckets().execute().await.unwrap();
+        assert_eq!(response.status(), 200);
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_build_storage_http2_prior_knowledge() {
+        let storage = Storage::builder("http://localhost:5000")
+            .http2_prior_knowledge()
+            .build();
+        assert!(storage.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_build_storage_redirect_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("GET", "/object/thefux/btc.pdf")
+            .with_status(302)
+            .with_header("location", "/edge/btc.pdf")
+            .expect(2)
+            .create_async()
+            .await;
+        let edge = server
+            .mock("GET", "/edge/btc.pdf")
+            .with_body("pdf")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let following = Storage::builder(server.url()).build().unwrap();
+        let response = following
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let not_following = Storage::builder(server.url())
+            .redirect_policy(Policy::none())
+            .build()
+            .unwrap();
+        let response = not_following
+            .from()
+            .get_object("thefux", "btc.pdf")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 302);
+        assert_eq!(response.headers()["location"], "/edge/btc.pdf");
+
+        redirect.assert_async().await;
+        edge.assert_async().await;
+    }
+
+    #[test]
+    fn test_build_storage_invalid_url() {
+        let error = Storage::builder("not a url").build().err().unwrap();
+        assert_eq!(error.error, "InvalidConfig");
+    }
+
+    #[test]
+    fn test_build_storage_api_version() {
+        let base_url = |url: &str, version: &str| {
+            Storage::builder(url)
+                .api_version(version)
+                .build()
+                .unwrap()
+                .base_url()
+                .to_string()
+        };
+
+        assert_eq!(
+            base_url("https://abcd1234.supabase.co", "v1"),
+            "https://abcd1234.supabase.co/storage/v1"
+        );
+        assert_eq!(
+            base_url("https://abcd1234.supabase.co/storage/v1/", "v1"),
+            "https://abcd1234.supabase.co/storage/v1"
+        );
+        assert_eq!(
+            base_url("http://localhost:8000/api/", "v2"),
+            "http://localhost:8000/api/storage/v2"
+        );
+
+        let error = Storage::builder("http://localhost")
+            .api_version("v1/object")
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(error.error, "InvalidConfig");
+    }
+
+    #[tokio::test]
+    async fn test_build_storage_decodes_gzip_by_default() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello supabase").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/object/thefux/hello.txt")
+            .with_header("content-encoding", "gzip")
+            .with_body(encoder.finish().unwrap())
+            .create_async()
+            .await;
+
+        let text = Storage::builder(server.url())
+            .build()
+            .unwrap()
+            .from()
+            .get_object("thefux", "hello.txt")
+            .execute()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(text, "hello supabase");
+    }
+}
@@ -0,0 +1,160 @@
+//! Helpers to test code using this crate without a live server.
+//!
+//! Available with the `testing` feature.
+//!
+//! # Example
+//! ```
+//! use std::sync::Arc;
+//! use supabase_storage::{testing::MockBackend, Storage};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let backend = Arc::new(MockBackend::new());
+//!     backend.push_response(200, &[], r#"{"message":"Successfully deleted"}"#);
+//!
+//!     let response = Storage::new("http://localhost/storage/v1")
+//!         .with_backend(backend.clone())
+//!         .from()
+//!         .delete_bucket("thefux")
+//!         .execute()
+//!         .await
+//!         .unwrap();
+//!
+//!     assert_eq!(response.status(), 200);
+//!     assert_eq!(backend.requests()[0].url.path(), "/storage/v1/bucket/thefux");
+//! }
+//! ```
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use async_trait::async_trait;
+use reqwest::{header::HeaderMap, Error, Method, Request, Response};
+use url::Url;
+
+use crate::build::backend::HttpBackend;
+
+/// A request captured by the `MockBackend`
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// the body, `None` for streamed bodies
+    pub body: Option<Vec<u8>>,
+}
+
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+/// A `HttpBackend` answering with queued responses and recording every request.
+///
+/// Responses are returned in the order they were pushed, once the queue is empty
+/// every request is answered with a `404`.
+#[derive(Default)]
+pub struct MockBackend {
+    responses: Mutex<VecDeque<MockResponse>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The status code of the response.
+    /// * `headers` - The headers of the response.
+    /// * `body` - The body of the response.
+    pub fn push_response(&self, status: u16, headers: &[(&str, &str)], body: impl Into<Vec<u8>>) {
+        self.responses.lock().unwrap().push_back(MockResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            body: body.into(),
+        });
+    }
+
+    /// The requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl HttpBackend for MockBackend {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| bytes.to_vec()),
+        });
+
+        let mock = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or(MockResponse {
+                status: 404,
+                headers: vec![],
+                body:
+                    br#"{"statusCode":"404","error":"not_found","message":"no mock response left"}"#
+                        .to_vec(),
+            });
+
+        let mut response = http::Response::builder().status(mock.status);
+        for (key, value) in mock.headers {
+            response = response.header(key, value);
+        }
+
+        Ok(response.body(mock.body).unwrap().into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use reqwest::Method;
+
+    use super::MockBackend;
+    use crate::{model::bucket::BucketDetails, Storage};
+
+    #[tokio::test]
+    async fn test_mock_backend() {
+        let backend = Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            &[("content-type", "application/json")],
+            r#"{"name":"thefux","id":"thefux","public":true}"#,
+        );
+
+        let bucket = Storage::new("http://localhost")
+            .with_backend(backend.clone())
+            .from()
+            .get_bucket_details("thefux")
+            .execute_from::<BucketDetails>()
+            .await
+            .unwrap();
+
+        assert_eq!(bucket.name, "thefux");
+        assert!(bucket.public);
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].url.path(), "/bucket/thefux");
+    }
+}
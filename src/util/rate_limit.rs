@@ -0,0 +1,189 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use tokio::time::Instant;
+
+use crate::model::errors;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// A token bucket limiting the number of requests per second.
+///
+/// Up to `requests_per_second` requests are sent at once, afterwards requests are spread evenly.
+/// Clones share the same bucket, so a single limiter can throttle several batches.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    capacity: f64,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a new `RateLimiter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests_per_second` - The number of requests allowed per second, must be positive.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<RateLimiter, errors::Error>` - The limiter, or an `InvalidRateLimit` error
+    ///   if `requests_per_second` is not a positive number.
+    ///
+    /// # Example
+    /// ```
+    /// use supabase_storage::util::rate_limit::RateLimiter;
+    ///
+    /// let limiter = RateLimiter::new(10.0).unwrap();
+    /// assert!(RateLimiter::new(0.0).is_err());
+    /// ```
+    pub fn new(requests_per_second: f64) -> Result<Self, errors::Error> {
+        if !(requests_per_second > 0.0 && requests_per_second.is_finite()) {
+            return Err(errors::Error {
+                status_code: "400".to_string(),
+                error: "InvalidRateLimit".to_string(),
+                message: format!(
+                    "requests_per_second must be positive, got {}",
+                    requests_per_second
+                ),
+                context: None,
+            });
+        }
+        let capacity = requests_per_second.ceil();
+        Ok(Self {
+            requests_per_second,
+            capacity,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            })),
+        })
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens =
+                    (bucket.tokens + elapsed * self.requests_per_second).min(self.capacity);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Parses a `Retry-After` header value, either a number of seconds or an HTTP-date.
+///
+/// Dates are compared against the local clock, dates in the past result in a zero duration.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use supabase_storage::util::rate_limit::parse_retry_after;
+///
+/// assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+/// assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+/// assert_eq!(parse_retry_after("soon"), None);
+/// ```
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    parse_retry_after_at(value, SystemTime::now())
+}
+
+fn parse_retry_after_at(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+/// The duration to wait before retrying, read from the `Retry-After` header of a response,
+/// e.g. of a `429 Too Many Requests`.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    use tokio::time::Instant;
+
+    use super::{parse_retry_after, parse_retry_after_at, retry_after, RateLimiter};
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("-1"), None);
+        assert_eq!(parse_retry_after("1.5"), None);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after_at("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after_at("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after_at("Wed, 21 Oct 2015", now), None);
+
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        let wait = parse_retry_after(&later).unwrap();
+        assert!(wait > Duration::from_secs(55) && wait <= Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_burst() {
+        let limiter = RateLimiter::new(3.0).unwrap();
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(333));
+    }
+
+    #[test]
+    fn test_rate_limiter_invalid() {
+        for requests_per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let error = RateLimiter::new(requests_per_second).unwrap_err();
+            assert_eq!(error.error, "InvalidRateLimit");
+        }
+    }
+}